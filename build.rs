@@ -0,0 +1,117 @@
+//! Finds every solver under `src/y<year>/day<day>/mod.rs` and generates the modules and the
+//! registry for them, so adding a day is just adding its directory.
+//!
+//! Parts with an answer in `answers.toml` for the `input.txt` next to their day also get
+//! `has_answer = "<year>-<day>-<part>"` set, so their real-input tests run. They're ignored
+//! elsewhere, as a day's input or answer can be missing from someone else's checkout.
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+fn main() -> io::Result<()> {
+    let root = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let src = root.join("src");
+    let answers = root.join("answers.toml");
+    println!("cargo:rerun-if-changed={}", src.display());
+    println!("cargo:rerun-if-changed={}", answers.display());
+    println!("cargo:rustc-check-cfg=cfg(has_answer, values(any()))");
+    let answered = answered_parts(&answers);
+
+    let mut modules = String::new();
+    let mut puzzles = String::new();
+
+    for (year, year_dir) in numbered_entries(&src, "y")? {
+        modules.push_str(&format!("pub mod y{} {{\n", year));
+
+        for (day, day_dir) in numbered_entries(&year_dir, "day")? {
+            let module = day_dir.join("mod.rs");
+            if !module.is_file() {
+                continue;
+            }
+            modules.push_str(&format!(
+                "    #[path = {:?}]\n    pub mod day{};\n",
+                module.display().to_string(),
+                day
+            ));
+            if let Ok(input) = fs::read_to_string(day_dir.join("input.txt")) {
+                let table = format!("{}.{}.{}", year, day, input_hash(&input));
+                for part in [1, 2] {
+                    if answered.contains(&format!("{}.part{}", table, part)) {
+                        println!("cargo:rustc-cfg=has_answer=\"{}-{}-{}\"", year, day, part);
+                    }
+                }
+            }
+            puzzles.push_str(&format!(
+                "    Puzzle {{ year: {}, day: {}, solver: &y{}::day{}::Day }},\n",
+                year, day, year, day
+            ));
+        }
+
+        modules.push_str("}\n");
+    }
+
+    let generated = format!(
+        "{}\n/// Every solver found by `build.rs`, ordered by year and day\npub static PUZZLES: &[Puzzle] = &[\n{}];\n",
+        modules, puzzles
+    );
+    let out = PathBuf::from(env::var("OUT_DIR").unwrap()).join("puzzles.rs");
+    fs::write(out, generated)
+}
+
+/// The answers in `answers.toml`, as `<year>.<day>.<input hash>.part<N>`. A missing or broken
+/// file just has no answers here, the runner reports what's wrong with it.
+fn answered_parts(path: &Path) -> Vec<String> {
+    let text = fs::read_to_string(path).unwrap_or_default();
+    let mut table = None;
+    let mut parts = vec![];
+
+    for line in text.lines().map(str::trim) {
+        if let Some(header) = line
+            .strip_prefix('[')
+            .and_then(|line| line.strip_suffix(']'))
+        {
+            table = Some(header.trim());
+        } else if let (Some(table), Some((key, _))) = (table, line.split_once('=')) {
+            parts.push(format!("{}.{}", table, key.trim()));
+        }
+    }
+
+    parts
+}
+
+/// The same hash as `answers::input_hash`, which can't be used from here
+fn input_hash(input: &str) -> String {
+    let hash = input
+        .trim_end()
+        .bytes()
+        .fold(0xcbf29ce484222325u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        });
+
+    format!("{:016x}", hash)
+}
+
+/// The directories in `dir` named `<prefix><number>`, sorted by the number
+fn numbered_entries(dir: &Path, prefix: &str) -> io::Result<Vec<(u32, PathBuf)>> {
+    let mut entries = vec![];
+
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if !path.is_dir() {
+            continue;
+        }
+        let number = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix(prefix))
+            .and_then(|number| number.parse::<u32>().ok());
+        if let Some(number) = number {
+            entries.push((number, path));
+        }
+    }
+    entries.sort();
+
+    Ok(entries)
+}
@@ -0,0 +1,12 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// The depth parsing in both parts, which have to return errors rather than panic
+fuzz_target!(|input: &str| {
+    let solver = aoc::find(2021, 1).unwrap().solver;
+
+    let _ = solver.parse(input);
+    let _ = solver.part1(input);
+    let _ = solver.part2(input);
+});
@@ -0,0 +1,12 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// `parse_commands` through `Solver::parse`, then `parse_movement` and following the route
+fuzz_target!(|input: &str| {
+    let solver = aoc::find(2021, 2).unwrap().solver;
+
+    let _ = solver.parse(input);
+    let _ = solver.part1(input);
+    let _ = solver.part2(input);
+});
@@ -0,0 +1,12 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// `DiagnosticReport::parse` through `Solver::parse`, then `get_bit_counts` and the rating filters
+fuzz_target!(|input: &str| {
+    let solver = aoc::find(2021, 3).unwrap().solver;
+
+    let _ = solver.parse(input);
+    let _ = solver.part1(input);
+    let _ = solver.part2(input);
+});
@@ -0,0 +1,12 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// `parse_game` and `Card::try_from` through `Solver::parse`, then playing the game
+fuzz_target!(|input: &str| {
+    let solver = aoc::find(2021, 4).unwrap().solver;
+
+    let _ = solver.parse(input);
+    let _ = solver.part1(input);
+    let _ = solver.part2(input);
+});
@@ -0,0 +1,376 @@
+//! The registry of known answers in `answers.toml`, keyed by year, day and a hash of the input,
+//! so everyone's answers can live side by side and be checked after solving.
+//!
+//! Only the small subset of TOML the registry needs is supported:
+//!
+//! ```toml
+//! # year.day.input-hash
+//! [2021.1.3b8a9e3c1f2d4e5a]
+//! part1 = "1709"
+//! part2 = 1761
+//! ```
+//!
+//! Quoted answers can use the escapes of TOML basic strings: `\"`, `\\`, `\n`, `\r`, `\t` and
+//! `\uXXXX`.
+
+use std::collections::BTreeMap;
+use std::error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::inputs::{DirFetcher, InputFetcher};
+use crate::{Answer, Result};
+
+#[derive(Debug, PartialEq)]
+pub struct AnswersError(String);
+
+impl fmt::Display for AnswersError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid answers file: {}", self.0)
+    }
+}
+impl error::Error for AnswersError {}
+
+/// The outcome of comparing an answer with the registry
+#[derive(Debug, PartialEq)]
+pub enum Check {
+    Pass,
+    /// The answer differs from the registered one, which is included
+    Fail(String),
+    /// There's no registered answer for this input and part
+    Unknown,
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Check::Pass => write!(f, "pass"),
+            Check::Fail(expected) => write!(f, "FAIL, expected {}", expected),
+            Check::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+/// Known answers by year, day and input hash, then by part
+#[derive(Debug, Default, PartialEq)]
+pub struct Answers {
+    entries: BTreeMap<(u16, u8, String), BTreeMap<u8, String>>,
+}
+
+impl Answers {
+    /// Load the registry at `path`, a missing file being an empty registry
+    pub fn load(path: &Path) -> Result<Answers> {
+        match fs::read_to_string(path) {
+            Ok(text) => Answers::parse(&text),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Answers::default()),
+            Err(e) => Err(Box::new(e)),
+        }
+    }
+
+    pub fn parse(text: &str) -> Result<Answers> {
+        let mut answers = Answers::default();
+        let mut section = None;
+
+        for (idx, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(header) = line.strip_prefix('[') {
+                let header = header
+                    .strip_suffix(']')
+                    .ok_or_else(|| error(idx, "unclosed table header"))?;
+                section = Some(parse_header(header).ok_or_else(|| {
+                    error(idx, "table headers must be [<year>.<day>.<input hash>]")
+                })?);
+                continue;
+            }
+
+            let (year, day, hash) = section
+                .clone()
+                .ok_or_else(|| error(idx, "answer outside of a table"))?;
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| error(idx, "expected part<N> = <answer>"))?;
+            let part = key
+                .trim()
+                .strip_prefix("part")
+                .and_then(|part| part.parse::<u8>().ok())
+                .filter(|part| matches!(part, 1 | 2))
+                .ok_or_else(|| error(idx, "keys must be part1 or part2"))?;
+            let value = parse_value(value.trim())
+                .ok_or_else(|| error(idx, "answers must be a quoted string or an integer"))?;
+
+            answers.insert(year, day, &hash, part, value);
+        }
+
+        Ok(answers)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        Ok(fs::write(path, self.to_string())?)
+    }
+
+    pub fn get(&self, year: u16, day: u8, hash: &str, part: u8) -> Option<&str> {
+        self.entries
+            .get(&(year, day, hash.to_string()))
+            .and_then(|parts| parts.get(&part))
+            .map(String::as_str)
+    }
+
+    pub fn insert(&mut self, year: u16, day: u8, hash: &str, part: u8, answer: String) {
+        self.entries
+            .entry((year, day, hash.to_string()))
+            .or_default()
+            .insert(part, answer);
+    }
+
+    /// Compare the answer to a part for `input` with the registered one
+    pub fn check(&self, year: u16, day: u8, part: u8, input: &str, answer: &Answer) -> Check {
+        match self.get(year, day, &input_hash(input), part) {
+            Some(expected) if expected == answer.to_string() => Check::Pass,
+            Some(expected) => Check::Fail(expected.to_string()),
+            None => Check::Unknown,
+        }
+    }
+}
+
+/// Written back out in the same format `parse` reads
+impl fmt::Display for Answers {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "# year.day.input-hash")?;
+        for ((year, day, hash), parts) in &self.entries {
+            writeln!(f)?;
+            writeln!(f, "[{}.{}.{}]", year, day, hash)?;
+            for (part, answer) in parts {
+                writeln!(f, "part{} = {}", part, quote(answer))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn error(idx: usize, message: &str) -> Box<dyn error::Error> {
+    Box::new(AnswersError(format!("line {}: {}", idx + 1, message)))
+}
+
+fn parse_header(header: &str) -> Option<(u16, u8, String)> {
+    let mut keys = header.split('.').map(str::trim);
+    let year = keys.next()?.parse().ok()?;
+    let day = keys.next()?.parse().ok()?;
+    let hash = keys.next()?;
+    if keys.next().is_some() || hash.is_empty() || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    Some((year, day, hash.to_string()))
+}
+
+fn parse_value(value: &str) -> Option<String> {
+    if let Some(quoted) = value.strip_prefix('"') {
+        return unquote(quoted.strip_suffix('"')?);
+    }
+
+    value.parse::<i128>().ok().map(|val| val.to_string())
+}
+
+/// An answer as a quoted string, escaped so that `unquote` gives it back
+fn quote(answer: &str) -> String {
+    let mut quoted = String::from('"');
+    for c in answer.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// The text between the quotes of an answer, with its escapes undone. Unescaped quotes and
+/// unknown escapes are invalid.
+fn unquote(text: &str) -> Option<String> {
+    let mut chars = text.chars();
+    let mut unquoted = String::new();
+
+    while let Some(c) = chars.next() {
+        let c = match c {
+            '"' => return None,
+            '\\' => match chars.next()? {
+                '"' => '"',
+                '\\' => '\\',
+                'n' => '\n',
+                'r' => '\r',
+                't' => '\t',
+                'u' => {
+                    let hex = chars.by_ref().take(4).collect::<String>();
+                    if hex.len() != 4 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                        return None;
+                    }
+                    char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?
+                }
+                _ => return None,
+            },
+            c => c,
+        };
+        unquoted.push(c);
+    }
+
+    Some(unquoted)
+}
+
+/// A short, stable hash of an input, ignoring trailing whitespace (64 bit FNV-1a)
+pub fn input_hash(input: &str) -> String {
+    let hash = input
+        .trim_end()
+        .bytes()
+        .fold(0xcbf29ce484222325u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        });
+
+    format!("{:016x}", hash)
+}
+
+/// The registry shared by the runner and the tests
+pub fn answers_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("answers.toml")
+}
+
+/// The real input for a day and the registered answer to a part, for tests to check against.
+/// The tests are ignored unless `build.rs` found both (see `has_answer`), so anything missing
+/// here is a failure rather than a reason to skip.
+///
+/// The input is read straight from the day's `input.txt`, so tests never download anything or
+/// share a cache.
+pub fn real_input(year: u16, day: u8, part: u8) -> (String, String) {
+    let input = DirFetcher::new(&crate::source_dir())
+        .fetch(year, day)
+        .unwrap_or_else(|e| panic!("Couldn't read the input for {} day {}: {}", year, day, e));
+
+    let answers = Answers::load(&answers_path()).expect("answers.toml should be readable");
+    match answers.get(year, day, &input_hash(&input), part) {
+        Some(expected) => (input, expected.to_string()),
+        None => panic!(
+            "No answer for {} day {} part {} with this input in answers.toml, add one with aoc submit",
+            year, day, part
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REGISTRY: &str = "# comment\n\n[2021.1.00ff]\npart1 = \"1709\"\npart2 = 1761\n\n[2021.3.abcd]\npart2 = \"3385170\"\n";
+
+    #[test]
+    fn test_parse() {
+        let answers = Answers::parse(REGISTRY).unwrap();
+
+        assert_eq!(answers.get(2021, 1, "00ff", 1), Some("1709"));
+        assert_eq!(answers.get(2021, 1, "00ff", 2), Some("1761"));
+        assert_eq!(answers.get(2021, 3, "abcd", 2), Some("3385170"));
+        assert_eq!(answers.get(2021, 3, "abcd", 1), None);
+        assert_eq!(answers.get(2021, 1, "abcd", 1), None);
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        let invalid = [
+            "part1 = \"1\"",
+            "[2021.1]\npart1 = \"1\"",
+            "[2021.1.xyz]\npart1 = \"1\"",
+            "[2021.1.ab\npart1 = \"1\"",
+            "[2021.1.ab]\nanswer = \"1\"",
+            "[2021.1.ab]\npart1 = one",
+            "[2021.1.ab]\npart1 = \"1",
+            "[2021.1.ab]\npart0 = \"1\"",
+            "[2021.1.ab]\npart3 = \"1\"",
+            "[2021.1.ab]\npart1 = \"a\"b\"",
+            "[2021.1.ab]\npart1 = \"a\\\"",
+            "[2021.1.ab]\npart1 = \"a\\q\"",
+            "[2021.1.ab]\npart1 = \"\\u00g1\"",
+        ];
+
+        for text in invalid {
+            assert!(
+                Answers::parse(text).is_err(),
+                "{:?} should be invalid",
+                text
+            );
+        }
+        assert_eq!(
+            Answers::parse("\n[2021.1.ab]\npart1 = one")
+                .unwrap_err()
+                .to_string(),
+            "Invalid answers file: line 3: answers must be a quoted string or an integer"
+        );
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let answers = Answers::parse(REGISTRY).unwrap();
+
+        assert_eq!(Answers::parse(&answers.to_string()).unwrap(), answers);
+    }
+
+    #[test]
+    fn test_escaped_answers() {
+        let mut answers = Answers::default();
+        for (part, answer) in [(1, "say \"hi\" \\o/"), (2, "tab\there\u{1}é")] {
+            answers.insert(2021, 1, "ab", part, answer.into());
+        }
+        let text = answers.to_string();
+
+        assert!(text.contains("part1 = \"say \\\"hi\\\" \\\\o/\"\n"));
+        assert!(text.contains("part2 = \"tab\\there\\u0001é\"\n"));
+        assert_eq!(Answers::parse(&text).unwrap(), answers);
+        assert_eq!(
+            Answers::parse("[2021.1.ab]\npart1 = \"\\u0041\\n\"")
+                .unwrap()
+                .get(2021, 1, "ab", 1),
+            Some("A\n")
+        );
+    }
+
+    #[test]
+    fn test_check() {
+        let input = "199\n200\n";
+        let mut answers = Answers::default();
+        answers.insert(2021, 1, &input_hash(input), 1, "7".into());
+
+        assert_eq!(
+            answers.check(2021, 1, 1, input, &Answer::Signed(7)),
+            Check::Pass
+        );
+        assert_eq!(
+            answers.check(2021, 1, 1, input, &Answer::Signed(8)),
+            Check::Fail("7".into())
+        );
+        assert_eq!(
+            answers.check(2021, 1, 2, input, &Answer::Signed(7)),
+            Check::Unknown
+        );
+    }
+
+    #[test]
+    fn test_input_hash() {
+        assert_eq!(input_hash(""), "cbf29ce484222325");
+        assert_eq!(input_hash("a"), "af63dc4c8601ec8c");
+        assert_eq!(input_hash("199\n200"), input_hash("199\n200\n"));
+        assert_ne!(input_hash("199\n200"), input_hash("200\n199"));
+    }
+
+    #[test]
+    fn test_registry_is_valid() {
+        assert!(Answers::load(&answers_path()).is_ok());
+    }
+}
@@ -0,0 +1,332 @@
+//! Timing the parsing and both parts of a day over many iterations, with a saved baseline of
+//! median times to flag regressions against.
+//!
+//! The baseline is a plain text file with a line per day and stage:
+//!
+//! ```text
+//! # year day stage median-nanoseconds
+//! 2021 3 part2 181234
+//! ```
+
+use std::collections::BTreeMap;
+use std::error;
+use std::fmt;
+use std::fs;
+use std::hint::black_box;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::{Puzzle, Result};
+
+/// What gets timed for each day, in order
+pub const STAGES: [&str; 3] = ["parse", "part1", "part2"];
+
+#[derive(Debug, PartialEq)]
+pub struct BenchError(String);
+
+impl fmt::Display for BenchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+impl error::Error for BenchError {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub p95: Duration,
+}
+
+impl Stats {
+    /// Summarise the time taken by each iteration, of which there must be at least one
+    pub fn from_samples(mut samples: Vec<Duration>) -> Stats {
+        samples.sort();
+        // Nearest rank, so the p95 of a handful of samples is the slowest one
+        let p95 = (samples.len() * 95).div_ceil(100) - 1;
+
+        Stats {
+            min: samples[0],
+            median: samples[samples.len() / 2],
+            p95: samples[p95],
+        }
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "min {:>10}  median {:>10}  p95 {:>10}",
+            format!("{:.1?}", self.min),
+            format!("{:.1?}", self.median),
+            format!("{:.1?}", self.p95)
+        )
+    }
+}
+
+/// The stats for one stage of one day
+#[derive(Debug, Clone, PartialEq)]
+pub struct Timing {
+    pub year: u16,
+    pub day: u8,
+    pub stage: &'static str,
+    pub stats: Stats,
+}
+
+/// Time `run` over `iterations` runs, after a warm up run that also stops early on errors
+pub fn measure(iterations: usize, mut run: impl FnMut() -> Result<()>) -> Result<Stats> {
+    if iterations == 0 {
+        return Err(Box::new(BenchError("Need at least one iteration".into())));
+    }
+    run()?;
+
+    let mut samples = Vec::with_capacity(iterations);
+    for _ in 0..iterations {
+        let start = Instant::now();
+        run()?;
+        samples.push(start.elapsed());
+    }
+
+    Ok(Stats::from_samples(samples))
+}
+
+/// Time every stage of a day on `input`
+pub fn bench_puzzle(puzzle: &Puzzle, input: &str, iterations: usize) -> Result<Vec<Timing>> {
+    let solver = puzzle.solver;
+
+    STAGES
+        .iter()
+        .map(|&stage| {
+            let stats = measure(iterations, || {
+                match stage {
+                    "parse" => black_box(solver.parse(black_box(input)))?,
+                    "part1" => {
+                        black_box(solver.part1(black_box(input))?);
+                    }
+                    _ => {
+                        black_box(solver.part2(black_box(input))?);
+                    }
+                }
+                Ok(())
+            })?;
+            Ok(Timing {
+                year: puzzle.year,
+                day: puzzle.day,
+                stage,
+                stats,
+            })
+        })
+        .collect()
+}
+
+/// How much slower a stage got compared to the baseline
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Change {
+    pub baseline: Duration,
+    /// The change in median time, in percent
+    pub percent: f64,
+}
+
+/// Saved median times by year, day and stage
+#[derive(Debug, Default, PartialEq)]
+pub struct Baseline {
+    medians: BTreeMap<(u16, u8, String), Duration>,
+}
+
+impl Baseline {
+    /// Load the baseline at `path`, a missing file being an empty baseline
+    pub fn load(path: &Path) -> Result<Baseline> {
+        match fs::read_to_string(path) {
+            Ok(text) => Baseline::parse(&text),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Baseline::default()),
+            Err(e) => Err(Box::new(e)),
+        }
+    }
+
+    pub fn parse(text: &str) -> Result<Baseline> {
+        let mut baseline = Baseline::default();
+
+        for (idx, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = || {
+                BenchError(format!(
+                    "Invalid baseline line {}, expected <year> <day> <stage> <nanoseconds>: {}",
+                    idx + 1,
+                    line
+                ))
+            };
+
+            let fields = line.split_whitespace().collect::<Vec<&str>>();
+            if fields.len() != 4 {
+                return Err(Box::new(invalid()));
+            }
+            let year = fields[0].parse().map_err(|_| invalid())?;
+            let day = fields[1].parse().map_err(|_| invalid())?;
+            let nanos = fields[3].parse().map_err(|_| invalid())?;
+
+            baseline
+                .medians
+                .insert((year, day, fields[2].into()), Duration::from_nanos(nanos));
+        }
+
+        Ok(baseline)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        Ok(fs::write(path, self.to_string())?)
+    }
+
+    pub fn get(&self, year: u16, day: u8, stage: &str) -> Option<Duration> {
+        self.medians.get(&(year, day, stage.to_string())).copied()
+    }
+
+    /// Keep the median of a timing, replacing any earlier one for the same stage
+    pub fn record(&mut self, timing: &Timing) {
+        self.medians.insert(
+            (timing.year, timing.day, timing.stage.into()),
+            timing.stats.median,
+        );
+    }
+
+    /// How the median of a timing compares to the baseline, if there is one for its stage
+    pub fn compare(&self, timing: &Timing) -> Option<Change> {
+        let baseline = self.get(timing.year, timing.day, timing.stage)?;
+        let percent = (timing.stats.median.as_secs_f64() / baseline.as_secs_f64() - 1.0) * 100.0;
+
+        Some(Change { baseline, percent })
+    }
+}
+
+impl fmt::Display for Baseline {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "# year day stage median-nanoseconds")?;
+        for ((year, day, stage), median) in &self.medians {
+            writeln!(f, "{} {} {} {}", year, day, stage, median.as_nanos())?;
+        }
+        Ok(())
+    }
+}
+
+/// Where baselines are saved by default. They're specific to a machine, so it isn't committed.
+pub fn baseline_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("bench-baseline.txt")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn micros(values: &[u64]) -> Vec<Duration> {
+        values
+            .iter()
+            .map(|&val| Duration::from_micros(val))
+            .collect()
+    }
+
+    fn timing(stage: &'static str, median: u64) -> Timing {
+        let median = Duration::from_micros(median);
+        Timing {
+            year: 2021,
+            day: 3,
+            stage,
+            stats: Stats {
+                min: median,
+                median,
+                p95: median,
+            },
+        }
+    }
+
+    #[test]
+    fn test_stats_from_samples() {
+        let samples = micros(&(1..=100).rev().collect::<Vec<u64>>());
+
+        assert_eq!(
+            Stats::from_samples(samples),
+            Stats {
+                min: Duration::from_micros(1),
+                median: Duration::from_micros(51),
+                p95: Duration::from_micros(95),
+            }
+        );
+        assert_eq!(
+            Stats::from_samples(micros(&[7])),
+            Stats {
+                min: Duration::from_micros(7),
+                median: Duration::from_micros(7),
+                p95: Duration::from_micros(7),
+            }
+        );
+        assert_eq!(
+            Stats::from_samples(micros(&[3, 1, 2])).p95,
+            Duration::from_micros(3)
+        );
+    }
+
+    #[test]
+    fn test_measure() {
+        let mut runs = 0;
+        measure(5, || {
+            runs += 1;
+            Ok(())
+        })
+        .unwrap();
+
+        // Including the warm up
+        assert_eq!(runs, 6);
+        assert!(measure(0, || Ok(())).is_err());
+        assert!(measure(5, || Err(Box::new(BenchError("failed".into())))).is_err());
+    }
+
+    #[test]
+    fn test_bench_puzzle() {
+        let puzzle = crate::find(2021, 1).unwrap();
+        let timings = bench_puzzle(puzzle, "199\n200\n208\n210\n200\n", 3).unwrap();
+
+        assert_eq!(
+            timings
+                .iter()
+                .map(|timing| timing.stage)
+                .collect::<Vec<&str>>(),
+            STAGES
+        );
+        assert!(bench_puzzle(puzzle, "199\ndeep\n", 3).is_err());
+    }
+
+    #[test]
+    fn test_baseline_round_trip() {
+        let mut baseline = Baseline::default();
+        baseline.record(&timing("part1", 120));
+        baseline.record(&timing("part2", 80));
+
+        assert_eq!(Baseline::parse(&baseline.to_string()).unwrap(), baseline);
+        assert_eq!(
+            baseline.get(2021, 3, "part1"),
+            Some(Duration::from_micros(120))
+        );
+        assert_eq!(baseline.get(2021, 3, "parse"), None);
+    }
+
+    #[test]
+    fn test_baseline_parse_invalid() {
+        assert!(Baseline::parse("2021 3 part1").is_err());
+        assert!(Baseline::parse("2021 3 part1 fast").is_err());
+        assert!(Baseline::parse("# comment\n\n2021 3 part1 1000\n").is_ok());
+    }
+
+    #[test]
+    fn test_compare() {
+        let mut baseline = Baseline::default();
+        baseline.record(&timing("part1", 100));
+
+        let change = baseline.compare(&timing("part1", 125)).unwrap();
+        assert_eq!(change.baseline, Duration::from_micros(100));
+        assert!((change.percent - 25.0).abs() < 1e-9);
+        assert!(baseline.compare(&timing("part2", 125)).is_none());
+    }
+}
@@ -0,0 +1,411 @@
+//! Puzzle inputs, kept in a cache directory outside the repository rather than committed, as the
+//! puzzle authors ask people not to share them.
+//!
+//! Each user's inputs are cached as `<cache>/<user>/<year>/day<day>.txt`. A day missing from the
+//! cache is fetched once, from any `input.txt` left in its source directory or else from the
+//! Advent of Code site with the session cookie in `AOC_SESSION`. An `input.txt` edited after it
+//! was cached is fetched again.
+//!
+//! - `AOC_CACHE_DIR` overrides the cache directory, which is otherwise `aoc` in the user's cache
+//!   directory
+//! - `AOC_USER` names the user the inputs belong to, `default` if it isn't set
+
+use std::env;
+use std::error;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::Result;
+
+const BASE_URL: &str = "https://adventofcode.com";
+
+/// Identifies the runner to the site, which asks automated tools to say who they are
+pub(crate) const USER_AGENT: &str = concat!("advent-of-code-2021/", env!("CARGO_PKG_VERSION"));
+
+#[derive(Debug, PartialEq)]
+pub struct InputError(String);
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+impl error::Error for InputError {}
+
+/// Somewhere inputs can be fetched from when they aren't cached yet
+pub trait InputFetcher {
+    fn fetch(&self, year: u16, day: u8) -> Result<String>;
+
+    /// When the input last changed, for sources that can change after it was cached
+    fn modified(&self, _year: u16, _day: u8) -> Option<SystemTime> {
+        None
+    }
+}
+
+/// Downloads inputs from the Advent of Code site, logged in with a session cookie
+pub struct HttpFetcher {
+    base_url: String,
+    session: String,
+}
+
+impl HttpFetcher {
+    pub fn new(session: &str) -> HttpFetcher {
+        HttpFetcher::with_base_url(BASE_URL, session)
+    }
+
+    /// Fetch from another server, like a local stand-in for the site
+    pub fn with_base_url(base_url: &str, session: &str) -> HttpFetcher {
+        HttpFetcher {
+            base_url: base_url.trim_end_matches('/').into(),
+            session: session.into(),
+        }
+    }
+}
+
+impl InputFetcher for HttpFetcher {
+    fn fetch(&self, year: u16, day: u8) -> Result<String> {
+        let url = format!("{}/{}/day/{}/input", self.base_url, year, day);
+        let response = ureq::get(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .set("User-Agent", USER_AGENT)
+            .call();
+
+        match response {
+            Ok(response) => Ok(response.into_string()?),
+            Err(ureq::Error::Status(404, _)) => Err(Box::new(InputError(format!(
+                "{} day {} isn't unlocked yet",
+                year, day
+            )))),
+            Err(ureq::Error::Status(400 | 401 | 403, _)) => Err(Box::new(InputError(
+                "The session cookie was rejected, log in again and update AOC_SESSION".into(),
+            ))),
+            Err(ureq::Error::Status(status, _)) => Err(Box::new(InputError(format!(
+                "Fetching {} failed with status {}",
+                url, status
+            )))),
+            Err(e) => Err(Box::new(e)),
+        }
+    }
+}
+
+/// Reads inputs from a directory laid out like `src`, as `y<year>/day<day>/input.txt`
+pub struct DirFetcher {
+    dir: PathBuf,
+}
+
+impl DirFetcher {
+    pub fn new(dir: &Path) -> DirFetcher {
+        DirFetcher { dir: dir.into() }
+    }
+
+    /// Where the input for a day is read from
+    pub fn path(&self, year: u16, day: u8) -> PathBuf {
+        self.dir
+            .join(format!("y{}", year))
+            .join(format!("day{}", day))
+            .join("input.txt")
+    }
+}
+
+impl InputFetcher for DirFetcher {
+    fn fetch(&self, year: u16, day: u8) -> Result<String> {
+        let path = self.path(year, day);
+        let input = fs::read_to_string(&path)
+            .map_err(|e| InputError(format!("Couldn't read {}: {}", path.display(), e)))?;
+        if input.trim().is_empty() {
+            return Err(Box::new(InputError(format!("{} is empty", path.display()))));
+        }
+
+        Ok(input)
+    }
+
+    fn modified(&self, year: u16, day: u8) -> Option<SystemTime> {
+        modified(&self.path(year, day))
+    }
+}
+
+/// Stands in for `HttpFetcher` when there's no session cookie, to say how to download inputs
+struct NoSession;
+
+impl InputFetcher for NoSession {
+    fn fetch(&self, _year: u16, _day: u8) -> Result<String> {
+        Err(Box::new(InputError(
+            "set AOC_SESSION to download it".into(),
+        )))
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// A user's cached inputs, filled in from the fetchers, tried in order, on first use
+pub struct InputStore {
+    dir: PathBuf,
+    fetchers: Vec<Box<dyn InputFetcher>>,
+}
+
+impl InputStore {
+    pub fn new(cache_dir: &Path, user: &str, fetchers: Vec<Box<dyn InputFetcher>>) -> Result<Self> {
+        if user.is_empty() || user.starts_with('.') || user.contains(['/', '\\']) {
+            return Err(Box::new(InputError(format!(
+                "Invalid user name: {:?}",
+                user
+            ))));
+        }
+
+        Ok(InputStore {
+            dir: cache_dir.join(user),
+            fetchers,
+        })
+    }
+
+    /// The store set up by the environment, fetching from `src` and then the site
+    pub fn from_env() -> Result<InputStore> {
+        let cache_dir = match env::var_os("AOC_CACHE_DIR") {
+            Some(dir) => PathBuf::from(dir),
+            None => cache_dir().ok_or_else(|| {
+                InputError("Couldn't find a cache directory, set AOC_CACHE_DIR".into())
+            })?,
+        };
+        let user = env::var("AOC_USER").unwrap_or_else(|_| "default".into());
+
+        let mut fetchers: Vec<Box<dyn InputFetcher>> =
+            vec![Box::new(DirFetcher::new(&crate::source_dir()))];
+        match env::var("AOC_SESSION") {
+            Ok(session) => fetchers.push(Box::new(HttpFetcher::new(session.trim()))),
+            Err(_) => fetchers.push(Box::new(NoSession)),
+        }
+
+        InputStore::new(&cache_dir, &user, fetchers)
+    }
+
+    /// The user's directory in the cache
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Where the input for a day is cached, whether or not it has been fetched
+    pub fn path(&self, year: u16, day: u8) -> PathBuf {
+        self.dir
+            .join(year.to_string())
+            .join(format!("day{}.txt", day))
+    }
+
+    /// The input for a day, fetching and caching it if it isn't cached yet or a fetcher has a
+    /// newer copy
+    pub fn get(&self, year: u16, day: u8) -> Result<String> {
+        let path = self.path(year, day);
+        if let Some(cached) = modified(&path) {
+            let stale = self
+                .fetchers
+                .iter()
+                .filter_map(|fetcher| fetcher.modified(year, day))
+                .any(|modified| modified >= cached);
+            if !stale {
+                return Ok(fs::read_to_string(path)?);
+            }
+        }
+
+        let mut errors = vec![];
+        for fetcher in &self.fetchers {
+            match fetcher.fetch(year, day) {
+                Ok(input) => {
+                    if let Some(dir) = path.parent() {
+                        fs::create_dir_all(dir)?;
+                    }
+                    fs::write(&path, &input)?;
+                    return Ok(input);
+                }
+                Err(e) => errors.push(e.to_string()),
+            }
+        }
+
+        Err(Box::new(InputError(format!(
+            "No input for {} day {}: {}",
+            year,
+            day,
+            errors.join("; ")
+        ))))
+    }
+}
+
+/// `aoc` in the platform's cache directory for the user
+fn cache_dir() -> Option<PathBuf> {
+    let dir = if cfg!(windows) {
+        PathBuf::from(env::var_os("LOCALAPPDATA")?)
+    } else if cfg!(target_os = "macos") {
+        PathBuf::from(env::var_os("HOME")?)
+            .join("Library")
+            .join("Caches")
+    } else {
+        match env::var_os("XDG_CACHE_HOME") {
+            Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => PathBuf::from(env::var_os("HOME")?).join(".cache"),
+        }
+    };
+
+    Some(dir.join("aoc"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_server::MockServer;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("aoc-inputs-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn site() -> MockServer {
+        MockServer::start(|request| {
+            match request.header("Cookie") {
+                Some("session=secret") => {}
+                Some("session=banned") => return (403, "Forbidden".into()),
+                _ => return (400, "Puzzle inputs differ by user. Please log in.".into()),
+            }
+            match request.path.as_str() {
+                "/2021/day/1/input" => (200, "199\n200\n".into()),
+                "/2021/day/2/input" => (500, "Internal Server Error".into()),
+                _ => (404, "Not Found".into()),
+            }
+        })
+    }
+
+    #[test]
+    fn test_http_fetcher() {
+        let site = site();
+        let fetcher = HttpFetcher::with_base_url(site.url(), "secret");
+
+        assert_eq!(fetcher.fetch(2021, 1).unwrap(), "199\n200\n");
+        let request = &site.requests()[0];
+        assert_eq!(request.method, "GET");
+        assert_eq!(request.header("User-Agent"), Some(USER_AGENT));
+
+        assert_eq!(
+            fetcher.fetch(2021, 25).unwrap_err().to_string(),
+            "2021 day 25 isn't unlocked yet"
+        );
+        assert!(HttpFetcher::with_base_url(site.url(), "expired")
+            .fetch(2021, 1)
+            .unwrap_err()
+            .to_string()
+            .contains("AOC_SESSION"));
+        assert!(HttpFetcher::with_base_url(site.url(), "banned")
+            .fetch(2021, 1)
+            .unwrap_err()
+            .to_string()
+            .contains("AOC_SESSION"));
+
+        let err = fetcher.fetch(2021, 2).unwrap_err().to_string();
+        assert!(err.ends_with("failed with status 500"), "{}", err);
+    }
+
+    #[test]
+    fn test_store_caches_fetched_inputs() {
+        let site = site();
+        let dir = temp_dir("cache");
+        let store = InputStore::new(
+            &dir,
+            "alice",
+            vec![Box::new(HttpFetcher::with_base_url(site.url(), "secret"))],
+        )
+        .unwrap();
+
+        assert_eq!(store.get(2021, 1).unwrap(), "199\n200\n");
+        assert_eq!(store.get(2021, 1).unwrap(), "199\n200\n");
+        assert_eq!(site.requests().len(), 1);
+        assert_eq!(
+            fs::read_to_string(dir.join("alice").join("2021").join("day1.txt")).unwrap(),
+            "199\n200\n"
+        );
+        assert!(store.get(2021, 2).is_err());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_store_tries_fetchers_in_order() {
+        let site = site();
+        let src = temp_dir("src");
+        let day = src.join("y2021").join("day2");
+        fs::create_dir_all(&day).unwrap();
+        fs::write(day.join("input.txt"), "forward 5\n").unwrap();
+
+        let dir = temp_dir("order");
+        let store = InputStore::new(
+            &dir,
+            "bob",
+            vec![
+                Box::new(DirFetcher::new(&src)),
+                Box::new(HttpFetcher::with_base_url(site.url(), "secret")),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(store.get(2021, 2).unwrap(), "forward 5\n");
+        assert_eq!(store.get(2021, 1).unwrap(), "199\n200\n");
+        assert_eq!(site.requests().len(), 1);
+
+        fs::remove_dir_all(src).unwrap();
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_store_refetches_edited_inputs() {
+        let src = temp_dir("edited");
+        let day = src.join("y2021").join("day1");
+        fs::create_dir_all(&day).unwrap();
+        fs::write(day.join("input.txt"), "199\n").unwrap();
+
+        let dir = temp_dir("refetch");
+        let store = InputStore::new(&dir, "carol", vec![Box::new(DirFetcher::new(&src))]).unwrap();
+        assert_eq!(store.get(2021, 1).unwrap(), "199\n");
+
+        // Date the cached copy back, as a quick edit could share its modification time
+        let hour_ago = SystemTime::now() - std::time::Duration::from_secs(3600);
+        let cached = fs::File::options()
+            .write(true)
+            .open(store.path(2021, 1))
+            .unwrap();
+        cached.set_modified(hour_ago).unwrap();
+        fs::write(day.join("input.txt"), "199\n200\n").unwrap();
+
+        assert_eq!(store.get(2021, 1).unwrap(), "199\n200\n");
+        assert_eq!(
+            fs::read_to_string(store.path(2021, 1)).unwrap(),
+            "199\n200\n"
+        );
+
+        fs::remove_dir_all(src).unwrap();
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_dir_fetcher_skips_empty_inputs() {
+        let src = temp_dir("empty");
+        let day = src.join("y2021").join("day3");
+        fs::create_dir_all(&day).unwrap();
+        fs::write(day.join("input.txt"), "\n").unwrap();
+
+        assert!(DirFetcher::new(&src).fetch(2021, 3).is_err());
+        assert!(DirFetcher::new(&src).fetch(2021, 4).is_err());
+
+        fs::remove_dir_all(src).unwrap();
+    }
+
+    #[test]
+    fn test_invalid_user() {
+        let dir = temp_dir("user");
+
+        assert!(InputStore::new(&dir, "", vec![]).is_err());
+        assert!(InputStore::new(&dir, "../alice", vec![]).is_err());
+        assert!(InputStore::new(&dir, "alice", vec![]).is_ok());
+    }
+}
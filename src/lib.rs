@@ -0,0 +1,200 @@
+//! Advent of Code solutions, one module per day in `src/y<year>/day<day>/mod.rs`.
+//!
+//! Each day module has a `Day` struct implementing `Solver`. The modules and the `PUZZLES`
+//! registry are generated by `build.rs`, so there's no list of days to keep up to date.
+
+use std::error;
+use std::fmt;
+use std::path::PathBuf;
+
+pub mod answers;
+pub mod bench;
+pub mod inputs;
+#[cfg(test)]
+mod mock_server;
+pub mod release;
+pub mod repl;
+pub mod report;
+pub mod scaffold;
+pub mod submit;
+pub mod watch;
+
+pub type Result<T> = std::result::Result<T, Box<dyn error::Error>>;
+
+include!(concat!(env!("OUT_DIR"), "/puzzles.rs"));
+
+/// The solution for a single day
+pub trait Solver: Sync {
+    /// Parse the input without solving anything, so benchmarks can time parsing on its own.
+    /// The parts take the raw input, so their timings include parsing as well.
+    fn parse(&self, _input: &str) -> Result<()> {
+        Ok(())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer>;
+
+    fn part2(&self, input: &str) -> Result<Answer>;
+
+    /// Extra commands for exploring the day, run with `aoc <day> <command> [args...]`
+    fn commands(&self) -> &'static [Command] {
+        &[]
+    }
+}
+
+/// A day specific command, which gets the arguments after its name and the puzzle input
+pub struct Command {
+    pub name: &'static str,
+    /// The arguments the command takes, for help output
+    pub usage: &'static str,
+    pub run: fn(&[String], &str) -> Result<()>,
+}
+
+/// A registered solver for a day of a year
+pub struct Puzzle {
+    pub year: u16,
+    pub day: u8,
+    pub solver: &'static dyn Solver,
+}
+
+impl Puzzle {
+    /// The example from the puzzle text, next to the day's module
+    pub fn example_path(&self) -> PathBuf {
+        source_dir()
+            .join(format!("y{}", self.year))
+            .join(format!("day{}", self.day))
+            .join("example.txt")
+    }
+
+    pub fn command(&self, name: &str) -> Option<&'static Command> {
+        self.solver
+            .commands()
+            .iter()
+            .find(|command| command.name == name)
+    }
+}
+
+/// The answer to a part of a puzzle
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Signed(i64),
+    Unsigned(u128),
+    Text(String),
+}
+
+impl Answer {
+    /// The name of the variant, for machine readable output
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Answer::Signed(_) => "signed",
+            Answer::Unsigned(_) => "unsigned",
+            Answer::Text(_) => "text",
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Signed(val) => write!(f, "{}", val),
+            Answer::Unsigned(val) => write!(f, "{}", val),
+            Answer::Text(val) => write!(f, "{}", val),
+        }
+    }
+}
+
+impl From<i32> for Answer {
+    fn from(val: i32) -> Self {
+        Answer::Signed(val.into())
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(val: i64) -> Self {
+        Answer::Signed(val)
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(val: usize) -> Self {
+        Answer::Unsigned(val as u128)
+    }
+}
+
+impl From<u128> for Answer {
+    fn from(val: u128) -> Self {
+        Answer::Unsigned(val)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(val: String) -> Self {
+        Answer::Text(val)
+    }
+}
+
+/// Look up the solver for a day
+pub fn find(year: u16, day: u8) -> Option<&'static Puzzle> {
+    PUZZLES
+        .iter()
+        .find(|puzzle| puzzle.year == year && puzzle.day == day)
+}
+
+/// The most recent year with any solvers
+pub fn latest_year() -> Option<u16> {
+    PUZZLES.iter().map(|puzzle| puzzle.year).max()
+}
+
+/// The `src` directory the day modules are found in
+pub fn source_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src")
+}
+
+/// The input for a day from the input store, fetching it if it isn't cached yet
+pub fn read_input(year: u16, day: u8) -> Result<String> {
+    inputs::InputStore::from_env()?.get(year, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_puzzles_are_discovered() {
+        let days = PUZZLES
+            .iter()
+            .filter(|puzzle| puzzle.year == 2021)
+            .map(|puzzle| puzzle.day)
+            .collect::<Vec<u8>>();
+
+        assert_eq!(&days[..4], &[1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_find() {
+        assert!(find(2021, 1).is_some());
+        assert!(find(2021, 26).is_none());
+        assert!(find(1999, 1).is_none());
+    }
+
+    #[test]
+    fn test_commands() {
+        let puzzle = find(2021, 3).unwrap();
+
+        assert!(puzzle.command("trace").is_some());
+        assert!(puzzle.command("unknown").is_none());
+    }
+
+    proptest! {
+        #[test]
+        fn prop_solvers_never_panic(
+            input in prop_oneof!["[01\n]{0,100}", "[0-9 ,\n-]{0,300}", "[a-z0-9 #\n-]{0,100}"]
+        ) {
+            for puzzle in PUZZLES {
+                let _ = puzzle.solver.parse(&input);
+                let _ = puzzle.solver.part1(&input);
+                let _ = puzzle.solver.part2(&input);
+            }
+        }
+    }
+}
@@ -0,0 +1,834 @@
+use std::env;
+use std::error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use aoc::answers::{Answers, Check};
+use aoc::bench::Baseline;
+use aoc::inputs::{DirFetcher, InputStore};
+use aoc::release::Version;
+use aoc::repl::Session;
+use aoc::report::{Format, PartResult};
+use aoc::submit::{History, HttpClient, Outcome};
+use aoc::watch::Watcher;
+use aoc::{Puzzle, PUZZLES};
+
+type Result<T> = std::result::Result<T, Box<dyn error::Error>>;
+
+const USAGE: &str = "Usage: aoc [--year <year>] [--input <path>] [--format text|json|csv] <day>
+       aoc [--year <year>] [--input <path>] <day> <command> [args...]
+       aoc list
+       aoc [--year <year>] new <day>
+       aoc [--year <year>] [--input <path>] repl <day>
+       aoc [--year <year>] [--input <path>] watch <day> [--example [path]] [--interval <ms>]
+       aoc [--year <year>] submit <day> <part>
+       aoc [--year <year>] version [--dry-run]
+       aoc [--year <year>] [--input <path>] bench [day] [--iterations <n>] [--threshold <percent>]
+           [--baseline <path>] [--save]";
+
+#[derive(Debug, PartialEq)]
+struct RunnerError(String);
+
+impl fmt::Display for RunnerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+impl error::Error for RunnerError {}
+
+/// The kinds of failure that get their own exit status, so scripts can tell them apart.
+/// Anything else, like bad arguments, exits with 1.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Failure {
+    /// The input file couldn't be read
+    Input,
+    /// A solver returned an error
+    Solver,
+    /// An answer didn't match the one in `answers.toml`
+    WrongAnswer,
+}
+
+impl Failure {
+    fn exit_code(self) -> i32 {
+        match self {
+            Failure::Input => 2,
+            Failure::Solver => 3,
+            Failure::WrongAnswer => 4,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Failed(Failure, String);
+
+impl fmt::Display for Failed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.1)
+    }
+}
+impl error::Error for Failed {}
+
+/// The options that can come before the day
+#[derive(Debug, Default, PartialEq)]
+struct Options {
+    year: Option<u16>,
+    input: Option<String>,
+    format: Format,
+}
+
+/// The options for `aoc bench`
+#[derive(Debug, PartialEq)]
+struct BenchOptions {
+    day: Option<String>,
+    iterations: usize,
+    /// How many percent slower than the baseline counts as a regression
+    threshold: f64,
+    baseline: PathBuf,
+    save: bool,
+}
+
+impl Default for BenchOptions {
+    fn default() -> Self {
+        BenchOptions {
+            day: None,
+            iterations: 100,
+            threshold: 10.0,
+            baseline: aoc::bench::baseline_path(),
+            save: false,
+        }
+    }
+}
+
+/// The options for `aoc watch`
+#[derive(Debug, PartialEq)]
+struct WatchOptions {
+    day: String,
+    /// Watch an example too, the day's `example.txt` unless a path is given
+    example: Option<Option<PathBuf>>,
+    interval: Duration,
+}
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<String>>();
+
+    if let Err(e) = run(&args) {
+        eprintln!("Error: {}", e);
+        let code = e
+            .downcast_ref::<Failed>()
+            .map_or(1, |failed| failed.0.exit_code());
+        process::exit(code);
+    }
+}
+
+fn run(args: &[String]) -> Result<()> {
+    let (options, rest) = parse_options(args)?;
+
+    match rest.first().map(String::as_str) {
+        Some("list") => list(),
+        Some("new") => new_day(options.year, rest.get(1)),
+        Some("bench") => bench(&options, &rest[1..]),
+        Some("repl") => repl(&options, rest.get(1)),
+        Some("watch") => watch(&options, &rest[1..]),
+        Some("submit") => submit(&options, &rest[1..]),
+        Some("version") => version(&options, &rest[1..]),
+        Some(day) => {
+            let puzzle = find_puzzle(options.year, day)?;
+            let input = read_input(&input_path(&options, puzzle)?)?;
+            match rest.get(1) {
+                Some(name) => run_command(puzzle, name, &rest[2..], &input),
+                None => solve(puzzle, &input, options.format),
+            }
+        }
+        None => Err(Box::new(RunnerError(USAGE.into()))),
+    }
+}
+
+/// The path to a day's input, from `--input` or the input store, which fetches it if needed
+fn input_path(options: &Options, puzzle: &Puzzle) -> Result<PathBuf> {
+    if let Some(path) = &options.input {
+        return Ok(PathBuf::from(path));
+    }
+
+    let store = InputStore::from_env()?;
+    store
+        .get(puzzle.year, puzzle.day)
+        .map_err(|e| Failed(Failure::Input, e.to_string()))?;
+    Ok(store.path(puzzle.year, puzzle.day))
+}
+
+fn read_input(path: &Path) -> Result<String> {
+    fs::read_to_string(path).map_err(|e| {
+        Failed(
+            Failure::Input,
+            format!("Couldn't read {}: {}", path.display(), e),
+        )
+        .into()
+    })
+}
+
+/// Split off the `--year`, `--input` and `--format` options from the rest of the arguments
+fn parse_options(args: &[String]) -> Result<(Options, &[String])> {
+    let mut options = Options::default();
+    let mut idx = 0;
+
+    while idx < args.len() {
+        match args[idx].as_str() {
+            "--year" => {
+                let year = args
+                    .get(idx + 1)
+                    .ok_or_else(|| RunnerError("--year needs a value".into()))?;
+                options.year = Some(year.parse()?);
+            }
+            "--input" => {
+                let path = args
+                    .get(idx + 1)
+                    .ok_or_else(|| RunnerError("--input needs a value".into()))?;
+                options.input = Some(path.clone());
+            }
+            "--format" => {
+                let format = args
+                    .get(idx + 1)
+                    .ok_or_else(|| RunnerError("--format needs a value".into()))?;
+                options.format = format.parse()?;
+            }
+            _ => break,
+        }
+        idx += 2;
+    }
+
+    Ok((options, &args[idx..]))
+}
+
+fn parse_bench_options(args: &[String]) -> Result<BenchOptions> {
+    let mut options = BenchOptions::default();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| RunnerError(format!("{} needs a value", arg)))
+        };
+        match arg.as_str() {
+            "--iterations" => options.iterations = value()?.parse()?,
+            "--threshold" => options.threshold = value()?.parse()?,
+            "--baseline" => options.baseline = PathBuf::from(value()?),
+            "--save" => options.save = true,
+            day if options.day.is_none() && !day.starts_with("--") => {
+                options.day = Some(day.into())
+            }
+            _ => {
+                return Err(Box::new(RunnerError(format!(
+                    "Unexpected bench argument: {}\n{}",
+                    arg, USAGE
+                ))))
+            }
+        }
+    }
+
+    Ok(options)
+}
+
+fn parse_watch_options(args: &[String]) -> Result<WatchOptions> {
+    let day = match args.first() {
+        Some(day) if !day.starts_with("--") => day.clone(),
+        _ => {
+            return Err(Box::new(RunnerError(format!(
+                "watch needs a day\n{}",
+                USAGE
+            ))))
+        }
+    };
+    let mut options = WatchOptions {
+        day,
+        example: None,
+        interval: Duration::from_millis(500),
+    };
+    let mut args = args[1..].iter().peekable();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--example" => {
+                let path = args.next_if(|path| !path.starts_with("--"));
+                options.example = Some(path.map(PathBuf::from));
+            }
+            "--interval" => {
+                let interval = args
+                    .next()
+                    .ok_or_else(|| RunnerError("--interval needs a value".into()))?;
+                options.interval = Duration::from_millis(interval.parse()?);
+            }
+            _ => {
+                return Err(Box::new(RunnerError(format!(
+                    "Unexpected watch argument: {}\n{}",
+                    arg, USAGE
+                ))))
+            }
+        }
+    }
+
+    Ok(options)
+}
+
+/// Find the solver for a day, in the given year or the most recent one
+fn find_puzzle(year: Option<u16>, day: &str) -> Result<&'static Puzzle> {
+    let year = match year.or_else(aoc::latest_year) {
+        Some(year) => year,
+        None => return Err(Box::new(RunnerError("No solvers registered".into()))),
+    };
+    let day = day
+        .parse::<u8>()
+        .map_err(|_| RunnerError(format!("Not a day: {}\n{}", day, USAGE)))?;
+
+    aoc::find(year, day)
+        .ok_or_else(|| Box::new(RunnerError(format!("No solver for {} day {}", year, day))).into())
+}
+
+fn list() -> Result<()> {
+    for puzzle in PUZZLES {
+        let commands = puzzle
+            .solver
+            .commands()
+            .iter()
+            .map(|command| command.name)
+            .collect::<Vec<&str>>();
+        if commands.is_empty() {
+            println!("{} day {}", puzzle.year, puzzle.day);
+        } else {
+            println!(
+                "{} day {} ({})",
+                puzzle.year,
+                puzzle.day,
+                commands.join(", ")
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Generate the module, input and example files for a day, in the given or latest year
+fn new_day(year: Option<u16>, day: Option<&String>) -> Result<()> {
+    let year = match year.or_else(aoc::latest_year) {
+        Some(year) => year,
+        None => return Err(Box::new(RunnerError("new needs --year <year>".into()))),
+    };
+    let day = day
+        .and_then(|day| day.parse::<u8>().ok())
+        .ok_or_else(|| RunnerError(format!("new needs a day\n{}", USAGE)))?;
+
+    let dir = aoc::scaffold::new_day(&aoc::source_dir(), year, day)?;
+    println!("Created {} day {} in {}", year, day, dir.display());
+    println!(
+        "Fill in example.txt and un-ignore its tests, then run: aoc --year {} {}",
+        year, day
+    );
+
+    Ok(())
+}
+
+/// Time parsing and both parts of a day, or every day, comparing with the saved baseline
+fn bench(options: &Options, args: &[String]) -> Result<()> {
+    let bench_options = parse_bench_options(args)?;
+    if cfg!(debug_assertions) {
+        eprintln!("Warning: benchmarking a debug build, use cargo run --release");
+    }
+
+    let puzzles = match &bench_options.day {
+        Some(day) => vec![find_puzzle(options.year, day)?],
+        None if options.input.is_some() => {
+            return Err(Box::new(RunnerError("--input needs a day to bench".into())))
+        }
+        None => PUZZLES
+            .iter()
+            .filter(|puzzle| options.year.is_none_or(|year| puzzle.year == year))
+            .collect(),
+    };
+
+    let mut baseline = Baseline::load(&bench_options.baseline)?;
+    let mut timings = vec![];
+    let mut regressions = 0;
+
+    for puzzle in puzzles {
+        let input = match input_path(options, puzzle).and_then(|path| read_input(&path)) {
+            Ok(input) => input,
+            // Benching everything shouldn't stop at the first day without an input
+            Err(e) if bench_options.day.is_none() => {
+                eprintln!("Skipping {} day {}: {}", puzzle.year, puzzle.day, e);
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+
+        for timing in aoc::bench::bench_puzzle(puzzle, &input, bench_options.iterations)? {
+            let note = match baseline.compare(&timing) {
+                Some(change) if change.percent > bench_options.threshold => {
+                    regressions += 1;
+                    format!(
+                        "  {:+.0}% vs {:.1?}, REGRESSION",
+                        change.percent, change.baseline
+                    )
+                }
+                Some(change) => format!("  {:+.0}% vs {:.1?}", change.percent, change.baseline),
+                None => String::new(),
+            };
+            println!(
+                "{} day {:>2} {:<5}  {}{}",
+                timing.year, timing.day, timing.stage, timing.stats, note
+            );
+            timings.push(timing);
+        }
+    }
+
+    if bench_options.save {
+        for timing in &timings {
+            baseline.record(timing);
+        }
+        baseline.save(&bench_options.baseline)?;
+        println!("Saved baseline to {}", bench_options.baseline.display());
+    }
+
+    if regressions > 0 {
+        return Err(Box::new(RunnerError(format!(
+            "{} stage(s) more than {}% slower than the baseline",
+            regressions, bench_options.threshold
+        ))));
+    }
+    Ok(())
+}
+
+/// Explore a day interactively, with its input loaded once
+fn repl(options: &Options, day: Option<&String>) -> Result<()> {
+    let day = day.ok_or_else(|| RunnerError(format!("repl needs a day\n{}", USAGE)))?;
+    let puzzle = find_puzzle(options.year, day)?;
+    let path = input_path(options, puzzle)?;
+    read_input(&path)?;
+
+    let mut session = Session::new(puzzle, path)?;
+    aoc::repl::run(&mut session, io::stdin().lock())
+}
+
+/// Solve both parts again whenever the input or example changes, until interrupted
+fn watch(options: &Options, args: &[String]) -> Result<()> {
+    let watch_options = parse_watch_options(args)?;
+    let puzzle = find_puzzle(options.year, &watch_options.day)?;
+
+    let mut files = vec![("input", input_path(options, puzzle)?)];
+    match watch_options.example {
+        Some(Some(path)) => files.push(("example", path)),
+        Some(None) => files.push(("example", puzzle.example_path())),
+        None => {}
+    }
+
+    let mut paths = files
+        .iter()
+        .map(|(_, path)| path.clone())
+        .collect::<Vec<PathBuf>>();
+    // Edits to the day's input.txt only reach the cached input through the store
+    let source = match options.input {
+        Some(_) => None,
+        None => Some(DirFetcher::new(&aoc::source_dir()).path(puzzle.year, puzzle.day)),
+    };
+    paths.extend(source.clone());
+    let mut watcher = Watcher::new(&paths);
+    let mut previous = files
+        .iter()
+        .map(|_| vec![])
+        .collect::<Vec<Vec<PartResult>>>();
+
+    println!(
+        "Watching {} day {}, press Ctrl-C to stop",
+        puzzle.year, puzzle.day
+    );
+    let mut changed = paths;
+    loop {
+        if source
+            .as_ref()
+            .is_some_and(|source| changed.contains(source))
+        {
+            match input_path(options, puzzle) {
+                Ok(path) => changed.push(path),
+                Err(e) => println!("{:<8} {}", "input", e),
+            }
+            // Take in the refreshed cache now rather than running again on the next poll
+            changed.extend(watcher.poll());
+        }
+        for (idx, (name, path)) in files.iter().enumerate() {
+            if !changed.contains(path) {
+                continue;
+            }
+            // Editors can leave a file missing for a moment while saving, so carry on
+            let input = match read_input(path) {
+                Ok(input) => input,
+                Err(e) => {
+                    println!("{:<8} {}", name, e);
+                    continue;
+                }
+            };
+            let answers = Answers::load(&aoc::answers::answers_path())?;
+            let results = aoc::report::solve(puzzle, &input, &answers);
+            print!("{}", aoc::watch::render(name, &previous[idx], &results));
+            previous[idx] = results;
+        }
+
+        changed = vec![];
+        while changed.is_empty() {
+            thread::sleep(watch_options.interval);
+            changed = watcher.poll();
+        }
+        println!();
+    }
+}
+
+/// Solve a part and submit the answer, unless it's known to be wrong or it's too soon after a wrong
+/// one, saving it to `answers.toml` when it's right
+fn submit(options: &Options, args: &[String]) -> Result<()> {
+    let (day, part) = match args {
+        [day, part] => (day, part),
+        _ => {
+            return Err(Box::new(RunnerError(format!(
+                "submit needs a day and a part\n{}",
+                USAGE
+            ))))
+        }
+    };
+    if options.input.is_some() {
+        return Err(Box::new(RunnerError(
+            "submit always uses the input from the input store".into(),
+        )));
+    }
+    let puzzle = find_puzzle(options.year, day)?;
+    let part = match part.as_str() {
+        "1" => 1,
+        "2" => 2,
+        _ => return Err(Box::new(RunnerError(format!("Not a part: {}", part)))),
+    };
+    let session = env::var("AOC_SESSION")
+        .map_err(|_| RunnerError("submit needs the session cookie in AOC_SESSION".into()))?;
+
+    let store = InputStore::from_env()?;
+    let input = store
+        .get(puzzle.year, puzzle.day)
+        .map_err(|e| Failed(Failure::Input, e.to_string()))?;
+    let answer = match part {
+        1 => puzzle.solver.part1(&input),
+        _ => puzzle.solver.part2(&input),
+    }
+    .map_err(|e| Failed(Failure::Solver, e.to_string()))?
+    .to_string();
+
+    let history_path = store.dir().join("submissions.txt");
+    let mut history = History::load(&history_path)?;
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+
+    println!(
+        "Submitting {} for {} day {} part {}",
+        answer, puzzle.year, puzzle.day, part
+    );
+    let outcome = aoc::submit::submit(
+        &HttpClient::new(session.trim()),
+        &mut history,
+        puzzle.year,
+        puzzle.day,
+        part,
+        &answer,
+        now,
+    )?;
+    history.save(&history_path)?;
+
+    match outcome {
+        Outcome::Correct => {
+            let answers_path = aoc::answers::answers_path();
+            let mut answers = Answers::load(&answers_path)?;
+            answers.insert(
+                puzzle.year,
+                puzzle.day,
+                &aoc::answers::input_hash(&input),
+                part,
+                answer,
+            );
+            answers.save(&answers_path)?;
+            println!("Correct, saved to {}", answers_path.display());
+            Ok(())
+        }
+        Outcome::AlreadySolved => {
+            println!("The site says this part is already solved, so it wasn't checked");
+            Ok(())
+        }
+        Outcome::Wait(_) => Err(Box::new(RunnerError(format!("Submitted {}", outcome)))),
+        _ => Err(Box::new(Failed(
+            Failure::WrongAnswer,
+            format!("{} is {}", answer, outcome),
+        ))),
+    }
+}
+
+/// Work out the version from the parts with verified answers, then update `Cargo.toml` and add the
+/// newly solved parts to the changelog
+fn version(options: &Options, args: &[String]) -> Result<()> {
+    let dry_run = match args {
+        [] => false,
+        [flag] if flag == "--dry-run" => true,
+        _ => {
+            return Err(Box::new(RunnerError(format!(
+                "Unexpected version arguments: {}\n{}",
+                args.join(" "),
+                USAGE
+            ))))
+        }
+    };
+    if options.input.is_some() {
+        return Err(Box::new(RunnerError(
+            "version always uses the inputs from the input store".into(),
+        )));
+    }
+    let year = match options.year.or_else(aoc::latest_year) {
+        Some(year) => year,
+        None => return Err(Box::new(RunnerError("No solvers registered".into()))),
+    };
+
+    let answers = Answers::load(&aoc::answers::answers_path())?;
+    let store = InputStore::from_env()?;
+    let mut solved = vec![];
+    for puzzle in PUZZLES.iter().filter(|puzzle| puzzle.year == year) {
+        match store.get(puzzle.year, puzzle.day) {
+            Ok(input) => solved.extend(aoc::release::solved(puzzle, &input, &answers)),
+            Err(e) => eprintln!("Skipping {} day {}: {}", puzzle.year, puzzle.day, e),
+        }
+    }
+    let version = Version::from_solved(&solved)
+        .ok_or_else(|| RunnerError(format!("Nothing verified as solved for {} yet", year)))?;
+
+    let manifest_path = aoc::release::manifest_path();
+    let manifest = fs::read_to_string(&manifest_path)?;
+    let current = aoc::release::package_version(&manifest).and_then(Version::from_semver);
+    if current.is_some_and(|current| current > version) {
+        return Err(Box::new(RunnerError(format!(
+            "Only {} is verified, but Cargo.toml is already past it, is an input missing?",
+            version
+        ))));
+    }
+    let changelog_path = aoc::release::changelog_path();
+    let changelog = match fs::read_to_string(&changelog_path) {
+        Ok(changelog) => changelog,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(Box::new(e)),
+    };
+
+    let new = aoc::release::unlisted(&changelog, &solved);
+    if new.is_empty() && current == Some(version) {
+        println!("Already at {}, nothing new solved", version);
+        return Ok(());
+    }
+    println!("{} ({} in Cargo.toml)", version, version.semver());
+    for solved in &new {
+        println!(
+            "  newly solved: {} day {} part {}",
+            solved.year, solved.day, solved.part
+        );
+    }
+    if dry_run {
+        return Ok(());
+    }
+
+    fs::write(
+        &manifest_path,
+        aoc::release::set_version(&manifest, &version.semver())?,
+    )?;
+    fs::write(
+        &changelog_path,
+        aoc::release::add_entry(&changelog, version, &new),
+    )?;
+    println!(
+        "Updated {} and {}",
+        manifest_path.display(),
+        changelog_path.display()
+    );
+
+    Ok(())
+}
+
+/// Solve both parts, checking the answers against `answers.toml`
+fn solve(puzzle: &Puzzle, input: &str, format: Format) -> Result<()> {
+    let answers = Answers::load(&aoc::answers::answers_path())?;
+    let results = aoc::report::solve(puzzle, input, &answers);
+    print!("{}", aoc::report::render(&results, format));
+
+    let errors = results
+        .iter()
+        .filter(|result| result.answer.is_err())
+        .count();
+    if errors > 0 {
+        return Err(Box::new(Failed(
+            Failure::Solver,
+            format!("{} part(s) couldn't be solved", errors),
+        )));
+    }
+    let wrong = results
+        .iter()
+        .filter(|result| matches!(result.check, Check::Fail(_)))
+        .count();
+    if wrong > 0 {
+        return Err(Box::new(Failed(
+            Failure::WrongAnswer,
+            format!("{} answer(s) don't match answers.toml", wrong),
+        )));
+    }
+    Ok(())
+}
+
+fn run_command(puzzle: &Puzzle, name: &str, args: &[String], input: &str) -> Result<()> {
+    match puzzle.command(name) {
+        Some(command) => (command.run)(args, input),
+        None => {
+            let usages = puzzle
+                .solver
+                .commands()
+                .iter()
+                .map(|command| {
+                    format!("    aoc {} {} {}", puzzle.day, command.name, command.usage)
+                        .trim_end()
+                        .to_string()
+                })
+                .collect::<Vec<String>>();
+            Err(Box::new(RunnerError(format!(
+                "Unknown command for {} day {}: {}\nAvailable commands:\n{}",
+                puzzle.year,
+                puzzle.day,
+                name,
+                usages.join("\n")
+            ))))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_options() {
+        let all = args(&[
+            "--year",
+            "2021",
+            "--input",
+            "example.txt",
+            "--format",
+            "json",
+            "3",
+            "trace",
+        ]);
+        let (options, rest) = parse_options(&all).unwrap();
+
+        assert_eq!(
+            options,
+            Options {
+                year: Some(2021),
+                input: Some("example.txt".into()),
+                format: Format::Json,
+            }
+        );
+        assert_eq!(rest, &args(&["3", "trace"])[..]);
+    }
+
+    #[test]
+    fn test_parse_options_invalid() {
+        assert!(parse_options(&args(&["--year"])).is_err());
+        assert!(parse_options(&args(&["--year", "twenty"])).is_err());
+        assert!(parse_options(&args(&["--format", "xml"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_bench_options() {
+        let all = args(&["3", "--iterations", "10", "--save", "--threshold", "25"]);
+
+        assert_eq!(
+            parse_bench_options(&all).unwrap(),
+            BenchOptions {
+                day: Some("3".into()),
+                iterations: 10,
+                threshold: 25.0,
+                save: true,
+                ..BenchOptions::default()
+            }
+        );
+        assert_eq!(parse_bench_options(&[]).unwrap(), BenchOptions::default());
+        assert!(parse_bench_options(&args(&["--iterations"])).is_err());
+        assert!(parse_bench_options(&args(&["--iterations", "many"])).is_err());
+        assert!(parse_bench_options(&args(&["3", "4"])).is_err());
+        assert!(parse_bench_options(&args(&["--fast"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_watch_options() {
+        assert_eq!(
+            parse_watch_options(&args(&["3"])).unwrap(),
+            WatchOptions {
+                day: "3".into(),
+                example: None,
+                interval: Duration::from_millis(500),
+            }
+        );
+        assert_eq!(
+            parse_watch_options(&args(&["3", "--example", "--interval", "100"])).unwrap(),
+            WatchOptions {
+                day: "3".into(),
+                example: Some(None),
+                interval: Duration::from_millis(100),
+            }
+        );
+        assert_eq!(
+            parse_watch_options(&args(&["3", "--example", "small.txt"]))
+                .unwrap()
+                .example,
+            Some(Some(PathBuf::from("small.txt")))
+        );
+        assert!(parse_watch_options(&[]).is_err());
+        assert!(parse_watch_options(&args(&["--example"])).is_err());
+        assert!(parse_watch_options(&args(&["3", "--interval"])).is_err());
+        assert!(parse_watch_options(&args(&["3", "4"])).is_err());
+    }
+
+    #[test]
+    fn test_submit_arguments() {
+        assert!(run(&args(&["submit", "1"])).is_err());
+        assert!(run(&args(&["submit", "1", "3"])).is_err());
+        assert!(run(&args(&["submit", "26", "1"])).is_err());
+        assert!(run(&args(&["--input", "example.txt", "submit", "1", "1"])).is_err());
+    }
+
+    #[test]
+    fn test_version_arguments() {
+        assert!(run(&args(&["version", "--force"])).is_err());
+        assert!(run(&args(&["--input", "example.txt", "version"])).is_err());
+    }
+
+    #[test]
+    fn test_exit_codes() {
+        let missing = args(&["--input", "/nonexistent/input.txt", "1"]);
+        let e = run(&missing).unwrap_err();
+
+        assert_eq!(
+            e.downcast_ref::<Failed>().map(|failed| failed.0),
+            Some(Failure::Input)
+        );
+        assert!(run(&args(&["--format"]))
+            .unwrap_err()
+            .downcast_ref::<Failed>()
+            .is_none());
+    }
+
+    #[test]
+    fn test_find_puzzle() {
+        assert_eq!(find_puzzle(Some(2021), "2").unwrap().day, 2);
+        assert!(find_puzzle(Some(2021), "two").is_err());
+        assert!(find_puzzle(Some(2021), "26").is_err());
+    }
+}
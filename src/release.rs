@@ -0,0 +1,277 @@
+//! Versioning by puzzle progress: once day 3 part 2 is solved the version is `v03.02`, which is
+//! `3.2.0` in `Cargo.toml` as semver doesn't allow leading zeros. The version follows a single
+//! year, as the day numbers start over each year.
+//!
+//! Each release gets an entry in `CHANGELOG.md` listing the parts solved since the last one, with
+//! a hash of each answer to tell which answer a part was verified with. The hash is unsalted, so
+//! it doesn't keep small numeric answers secret:
+//!
+//! ```text
+//! ## v03.02
+//!
+//! - 2021 day 3 part 2, answer hash `9e1b6c9c4ef1b3b5`
+//! ```
+
+use std::error;
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::answers::{input_hash, Answers, Check};
+use crate::{Puzzle, Result};
+
+#[derive(Debug, PartialEq)]
+pub struct ReleaseError(String);
+
+impl fmt::Display for ReleaseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+impl error::Error for ReleaseError {}
+
+/// A part whose registered answer the solver gives for the input
+#[derive(Debug, Clone, PartialEq)]
+pub struct Solved {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    pub answer_hash: String,
+}
+
+impl Solved {
+    /// How it's listed in the changelog, up to the answer hash
+    fn prefix(&self) -> String {
+        format!("- {} day {} part {},", self.year, self.day, self.part)
+    }
+}
+
+/// How far into one year's puzzles the solutions are
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version {
+    pub day: u8,
+    pub part: u8,
+}
+
+impl Version {
+    /// The version for the furthest part solved, out of parts from the same year
+    pub fn from_solved(solved: &[Solved]) -> Option<Version> {
+        solved
+            .iter()
+            .map(|solved| Version {
+                day: solved.day,
+                part: solved.part,
+            })
+            .max()
+    }
+
+    /// Read a version back from `Cargo.toml`, if it's one of these
+    pub fn from_semver(version: &str) -> Option<Version> {
+        let mut numbers = version.split('.');
+        let day = numbers.next()?.parse().ok()?;
+        let part = numbers.next()?.parse().ok()?;
+
+        Some(Version { day, part })
+    }
+
+    pub fn semver(&self) -> String {
+        format!("{}.{}.0", self.day, self.part)
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "v{:02}.{:02}", self.day, self.part)
+    }
+}
+
+/// The parts of a day with a registered answer for `input` that the solver still gives
+pub fn solved(puzzle: &Puzzle, input: &str, answers: &Answers) -> Vec<Solved> {
+    (1..=2)
+        .filter_map(|part| {
+            let answer = match part {
+                1 => puzzle.solver.part1(input),
+                _ => puzzle.solver.part2(input),
+            }
+            .ok()?;
+            match answers.check(puzzle.year, puzzle.day, part, input, &answer) {
+                Check::Pass => Some(Solved {
+                    year: puzzle.year,
+                    day: puzzle.day,
+                    part,
+                    // The same hash as for inputs does for answers too
+                    answer_hash: input_hash(&answer.to_string()),
+                }),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// The line of the `[package]` version in a `Cargo.toml`, and the version
+fn version_line(manifest: &str) -> Option<(usize, &str)> {
+    let mut in_package = false;
+
+    for (idx, line) in manifest.lines().enumerate() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_package = line == "[package]";
+            continue;
+        }
+        let value = match line.split_once('=') {
+            Some((key, value)) if in_package && key.trim() == "version" => {
+                value.split('#').next().unwrap_or_default().trim()
+            }
+            _ => continue,
+        };
+        return Some((idx, value.strip_prefix('"')?.strip_suffix('"')?));
+    }
+
+    None
+}
+
+/// The package version in a `Cargo.toml`
+pub fn package_version(manifest: &str) -> Option<&str> {
+    version_line(manifest).map(|(_, version)| version)
+}
+
+/// Set the package version in a `Cargo.toml`, keeping everything else as it was
+pub fn set_version(manifest: &str, version: &str) -> Result<String> {
+    let (line_idx, current) = version_line(manifest)
+        .ok_or_else(|| ReleaseError("Couldn't find the package version in Cargo.toml".into()))?;
+
+    Ok(manifest
+        .split_inclusive('\n')
+        .enumerate()
+        .map(|(idx, line)| {
+            if idx == line_idx {
+                let quoted = |version| format!("\"{}\"", version);
+                line.replacen(&quoted(current), &quoted(version), 1)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect())
+}
+
+/// The solved parts the changelog doesn't list yet
+pub fn unlisted<'a>(changelog: &str, solved: &'a [Solved]) -> Vec<&'a Solved> {
+    solved
+        .iter()
+        .filter(|solved| {
+            let prefix = solved.prefix();
+            !changelog.lines().any(|line| line.starts_with(&prefix))
+        })
+        .collect()
+}
+
+/// Add an entry for a release to the top of the changelog, under its title
+pub fn add_entry(changelog: &str, version: Version, solved: &[&Solved]) -> String {
+    let mut entry = format!("## {}\n\n", version);
+    for solved in solved {
+        entry.push_str(&format!(
+            "{} answer hash `{}`\n",
+            solved.prefix(),
+            solved.answer_hash
+        ));
+    }
+    entry.push('\n');
+
+    match changelog.split_once('\n') {
+        Some((title, rest)) if title.starts_with("# ") => {
+            format!("{}\n\n{}{}", title, entry, rest.trim_start_matches('\n'))
+        }
+        _ if changelog.trim().is_empty() => format!("# Changelog\n\n{}", entry),
+        _ => format!("{}{}", entry, changelog),
+    }
+}
+
+pub fn manifest_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml")
+}
+
+pub fn changelog_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("CHANGELOG.md")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = "[package]\nname = \"aoc\"\nversion = \"0.1.0\" # bumped by aoc version\n\n[dependencies]\nversion = \"1\"\n";
+
+    fn solved(day: u8, part: u8) -> Solved {
+        Solved {
+            year: 2021,
+            day,
+            part,
+            answer_hash: format!("{:016x}", day as u64 * 10 + part as u64),
+        }
+    }
+
+    #[test]
+    fn test_version() {
+        assert_eq!(Version::from_solved(&[]), None);
+
+        let version = Version::from_solved(&[solved(3, 1), solved(1, 2), solved(2, 2)]).unwrap();
+        assert_eq!(version, Version { day: 3, part: 1 });
+        assert_eq!(version.to_string(), "v03.01");
+        assert_eq!(version.semver(), "3.1.0");
+        assert_eq!(Version::from_semver("3.1.0"), Some(version));
+        assert_eq!(Version::from_semver("three"), None);
+    }
+
+    #[test]
+    fn test_solved() {
+        let puzzle = crate::find(2021, 1).unwrap();
+        let input = include_str!("y2021/day1/example.txt");
+        let mut answers = Answers::default();
+        answers.insert(2021, 1, &input_hash(input), 1, "7".into());
+        answers.insert(2021, 1, &input_hash(input), 2, "6".into());
+
+        assert_eq!(
+            super::solved(puzzle, input, &answers),
+            vec![Solved {
+                year: 2021,
+                day: 1,
+                part: 1,
+                answer_hash: input_hash("7"),
+            }]
+        );
+        assert!(super::solved(puzzle, input, &Answers::default()).is_empty());
+    }
+
+    #[test]
+    fn test_set_version() {
+        assert_eq!(package_version(MANIFEST), Some("0.1.0"));
+
+        let manifest = set_version(MANIFEST, "4.2.0").unwrap();
+        assert_eq!(
+            manifest,
+            MANIFEST.replace("version = \"0.1.0\"", "version = \"4.2.0\"")
+        );
+        assert_eq!(package_version(&manifest), Some("4.2.0"));
+        assert!(set_version("[dependencies]\nversion = \"1\"\n", "4.2.0").is_err());
+    }
+
+    #[test]
+    fn test_changelog() {
+        let all = [solved(1, 1), solved(1, 2), solved(2, 1)];
+        let first = add_entry("", Version { day: 1, part: 2 }, &unlisted("", &all[..2]));
+        assert_eq!(
+            first,
+            concat!(
+                "# Changelog\n\n## v01.02\n\n",
+                "- 2021 day 1 part 1, answer hash `000000000000000b`\n",
+                "- 2021 day 1 part 2, answer hash `000000000000000c`\n\n"
+            )
+        );
+
+        let new = unlisted(&first, &all);
+        assert_eq!(new, vec![&all[2]]);
+        let second = add_entry(&first, Version { day: 2, part: 1 }, &new);
+        assert!(second.starts_with(
+            "# Changelog\n\n## v02.01\n\n- 2021 day 2 part 1, answer hash `0000000000000015`\n\n## v01.02\n"
+        ));
+        assert!(unlisted(&second, &all).is_empty());
+    }
+}
@@ -0,0 +1,197 @@
+//! An interactive session that loads a day's input once and runs the parts and the day's
+//! commands against it, reloading the file on request.
+
+use std::error;
+use std::fmt;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+use std::time::Instant;
+
+use crate::{Puzzle, Result};
+
+#[derive(Debug, PartialEq)]
+pub struct ReplError(String);
+
+impl fmt::Display for ReplError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+impl error::Error for ReplError {}
+
+/// Whether to keep reading commands after one has run
+#[derive(Debug, PartialEq)]
+pub enum Flow {
+    Continue,
+    Quit,
+}
+
+pub struct Session {
+    puzzle: &'static Puzzle,
+    path: PathBuf,
+    input: String,
+}
+
+impl Session {
+    /// Start a session for a day with the input at `path`
+    pub fn new(puzzle: &'static Puzzle, path: PathBuf) -> Result<Session> {
+        let input = fs::read_to_string(&path)?;
+
+        Ok(Session {
+            puzzle,
+            path,
+            input,
+        })
+    }
+
+    /// Read the input file again, keeping the old input if that fails
+    pub fn reload(&mut self) -> Result<()> {
+        self.input = fs::read_to_string(&self.path)?;
+        Ok(())
+    }
+
+    /// Run one line of input, which is a command and its arguments separated by whitespace
+    pub fn eval(&mut self, line: &str) -> Result<Flow> {
+        let mut words = line.split_whitespace();
+        let name = match words.next() {
+            Some(name) => name,
+            None => return Ok(Flow::Continue),
+        };
+        let args = words.map(String::from).collect::<Vec<String>>();
+
+        match name {
+            "part1" | "part2" => {
+                let start = Instant::now();
+                let answer = match name {
+                    "part1" => self.puzzle.solver.part1(&self.input)?,
+                    _ => self.puzzle.solver.part2(&self.input)?,
+                };
+                println!("Part {}: {} ({:.1?})", &name[4..], answer, start.elapsed());
+            }
+            "reload" => {
+                self.reload()?;
+                println!(
+                    "Reloaded {}, {} lines",
+                    self.path.display(),
+                    self.input.lines().count()
+                );
+            }
+            "help" => print!("{}", self.help()),
+            "quit" | "exit" => return Ok(Flow::Quit),
+            name => match self.puzzle.command(name) {
+                Some(command) => (command.run)(&args, &self.input)?,
+                None => {
+                    return Err(Box::new(ReplError(format!(
+                        "Unknown command: {}, try help",
+                        name
+                    ))))
+                }
+            },
+        }
+
+        Ok(Flow::Continue)
+    }
+
+    fn help(&self) -> String {
+        let mut help = String::from(
+            "part1, part2    solve a part\nreload          read the input file again\nquit            leave\n",
+        );
+        for command in self.puzzle.solver.commands() {
+            let usage = format!("{} {}", command.name, command.usage);
+            help.push_str(&format!(
+                "{:<15} day {} command\n",
+                usage.trim_end(),
+                self.puzzle.day
+            ));
+        }
+        help
+    }
+}
+
+/// Read commands from `lines` until they run out or the session is quit. Errors are printed and
+/// the session carries on.
+pub fn run(session: &mut Session, mut lines: impl BufRead) -> Result<()> {
+    println!(
+        "{} day {} with {}, type help for the commands",
+        session.puzzle.year,
+        session.puzzle.day,
+        session.path.display()
+    );
+
+    loop {
+        print!("{}/{}> ", session.puzzle.year, session.puzzle.day);
+        io::stdout().flush()?;
+
+        let mut line = String::new();
+        if lines.read_line(&mut line)? == 0 {
+            println!();
+            return Ok(());
+        }
+        match session.eval(&line) {
+            Ok(Flow::Quit) => return Ok(()),
+            Ok(Flow::Continue) => {}
+            Err(e) => println!("Error: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn session(name: &str, input: &str) -> Session {
+        let path = env::temp_dir().join(format!("aoc-repl-{}-{}.txt", name, std::process::id()));
+        fs::write(&path, input).unwrap();
+
+        Session::new(crate::find(2021, 1).unwrap(), path).unwrap()
+    }
+
+    #[test]
+    fn test_eval() {
+        let mut session = session("eval", "199\n200\n208\n210\n");
+
+        assert_eq!(session.eval("part1").unwrap(), Flow::Continue);
+        assert_eq!(session.eval("  window 2 ").unwrap(), Flow::Continue);
+        assert_eq!(session.eval("").unwrap(), Flow::Continue);
+        assert_eq!(session.eval("quit").unwrap(), Flow::Quit);
+        assert!(session.eval("window").is_err());
+        assert!(session.eval("unknown").is_err());
+
+        fs::remove_file(&session.path).unwrap();
+    }
+
+    #[test]
+    fn test_reload() {
+        let mut session = session("reload", "199\n200\n");
+        fs::write(&session.path, "199\n200\n208\n").unwrap();
+
+        session.eval("reload").unwrap();
+        assert_eq!(session.input, "199\n200\n208\n");
+
+        // A failed reload keeps the input that was there
+        fs::remove_file(&session.path).unwrap();
+        assert!(session.eval("reload").is_err());
+        assert_eq!(session.input, "199\n200\n208\n");
+    }
+
+    #[test]
+    fn test_run() {
+        let mut session = session("run", "199\n200\n");
+
+        run(&mut session, "part1\nunknown\nquit\npart2\n".as_bytes()).unwrap();
+        run(&mut session, "part1\n".as_bytes()).unwrap();
+
+        fs::remove_file(&session.path).unwrap();
+    }
+
+    #[test]
+    fn test_help() {
+        let session = session("help", "199\n");
+
+        assert!(session.help().contains("window <size>   day 1 command\n"));
+
+        fs::remove_file(&session.path).unwrap();
+    }
+}
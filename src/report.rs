@@ -0,0 +1,320 @@
+//! Solving both parts of a day and reporting the results as text, JSON or CSV.
+//!
+//! Answers are always strings in JSON and CSV, as `u128` answers don't fit in a JSON number.
+
+use std::error;
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use crate::answers::{input_hash, Answers, Check};
+use crate::{Answer, Puzzle};
+
+#[derive(Debug, PartialEq)]
+pub struct ReportError(String);
+
+impl fmt::Display for ReportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+impl error::Error for ReportError {}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Format {
+    #[default]
+    Text,
+    Json,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = ReportError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(ReportError(format!(
+                "Unknown format {}, expected text, json or csv",
+                s
+            ))),
+        }
+    }
+}
+
+/// The outcome of solving one part
+#[derive(Debug, PartialEq)]
+pub struct PartResult {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    pub input_hash: String,
+    pub duration: Duration,
+    /// The answer, or the error the solver gave
+    pub answer: Result<Answer, String>,
+    /// How the answer compares to the registry, `Unknown` when there's no answer
+    pub check: Check,
+}
+
+impl PartResult {
+    pub fn status(&self) -> &'static str {
+        match (&self.answer, &self.check) {
+            (Err(_), _) => "error",
+            (Ok(_), Check::Pass) => "pass",
+            (Ok(_), Check::Fail(_)) => "fail",
+            (Ok(_), Check::Unknown) => "unknown",
+        }
+    }
+
+    fn fields(&self) -> [(&'static str, Option<String>); 10] {
+        let (answer, answer_type, error) = match &self.answer {
+            Ok(answer) => (Some(answer.to_string()), Some(answer.kind().into()), None),
+            Err(e) => (None, None, Some(e.clone())),
+        };
+        let expected = match &self.check {
+            Check::Fail(expected) => Some(expected.clone()),
+            _ => None,
+        };
+
+        [
+            ("year", Some(self.year.to_string())),
+            ("day", Some(self.day.to_string())),
+            ("part", Some(self.part.to_string())),
+            ("answer", answer),
+            ("answer_type", answer_type),
+            ("status", Some(self.status().into())),
+            ("expected", expected),
+            ("duration_ns", Some(self.duration.as_nanos().to_string())),
+            ("input_hash", Some(self.input_hash.clone())),
+            ("error", error),
+        ]
+    }
+}
+
+/// Solve both parts, timing each and checking the answers against the registry
+pub fn solve(puzzle: &Puzzle, input: &str, answers: &Answers) -> Vec<PartResult> {
+    let hash = input_hash(input);
+
+    (1..=2)
+        .map(|part| {
+            let start = Instant::now();
+            let answer = match part {
+                1 => puzzle.solver.part1(input),
+                _ => puzzle.solver.part2(input),
+            };
+            let duration = start.elapsed();
+
+            let answer = answer.map_err(|e| e.to_string());
+            let check = match &answer {
+                Ok(answer) => answers.check(puzzle.year, puzzle.day, part, input, answer),
+                Err(_) => Check::Unknown,
+            };
+            PartResult {
+                year: puzzle.year,
+                day: puzzle.day,
+                part,
+                input_hash: hash.clone(),
+                duration,
+                answer,
+                check,
+            }
+        })
+        .collect()
+}
+
+pub fn render(results: &[PartResult], format: Format) -> String {
+    match format {
+        Format::Text => render_text(results),
+        Format::Json => render_json(results),
+        Format::Csv => render_csv(results),
+    }
+}
+
+fn render_text(results: &[PartResult]) -> String {
+    results
+        .iter()
+        .map(|result| match &result.answer {
+            Ok(answer) => format!(
+                "Day {} - Part {}: {} ({}, {:.1?})\n",
+                result.day, result.part, answer, result.check, result.duration
+            ),
+            Err(e) => format!("Day {} - Part {}: error: {}\n", result.day, result.part, e),
+        })
+        .collect()
+}
+
+/// A JSON array with an object per part
+fn render_json(results: &[PartResult]) -> String {
+    let objects = results
+        .iter()
+        .map(|result| {
+            let fields = result
+                .fields()
+                .iter()
+                .map(|(name, value)| {
+                    let value = match (*name, value) {
+                        (_, None) => "null".into(),
+                        ("year" | "day" | "part" | "duration_ns", Some(value)) => value.clone(),
+                        (_, Some(value)) => json_string(value),
+                    };
+                    format!("\"{}\": {}", name, value)
+                })
+                .collect::<Vec<String>>();
+            format!("  {{{}}}", fields.join(", "))
+        })
+        .collect::<Vec<String>>();
+
+    format!("[\n{}\n]\n", objects.join(",\n"))
+}
+
+/// A header, then a row per part, with missing values left empty
+fn render_csv(results: &[PartResult]) -> String {
+    let mut csv = String::new();
+    if let Some(result) = results.first() {
+        let header = result
+            .fields()
+            .iter()
+            .map(|(name, _)| *name)
+            .collect::<Vec<&str>>();
+        csv.push_str(&header.join(","));
+        csv.push('\n');
+    }
+
+    for result in results {
+        let row = result
+            .fields()
+            .iter()
+            .map(|(_, value)| csv_field(value.as_deref().unwrap_or("")))
+            .collect::<Vec<String>>();
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+
+    csv
+}
+
+pub(crate) fn json_string(value: &str) -> String {
+    let mut json = String::from('"');
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// Quote a field if it has a comma, quote or line break in it
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn results() -> Vec<PartResult> {
+        vec![
+            PartResult {
+                year: 2021,
+                day: 1,
+                part: 1,
+                input_hash: "00ff".into(),
+                duration: Duration::from_micros(12),
+                answer: Ok(Answer::Signed(7)),
+                check: Check::Fail("8".into()),
+            },
+            PartResult {
+                year: 2021,
+                day: 1,
+                part: 2,
+                input_hash: "00ff".into(),
+                duration: Duration::from_nanos(500),
+                answer: Err("Invalid input: \"deep\", line 2".into()),
+                check: Check::Unknown,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_format_from_str() {
+        assert_eq!("json".parse::<Format>(), Ok(Format::Json));
+        assert_eq!("csv".parse::<Format>(), Ok(Format::Csv));
+        assert_eq!("text".parse::<Format>(), Ok(Format::Text));
+        assert!("xml".parse::<Format>().is_err());
+    }
+
+    #[test]
+    fn test_render_text() {
+        assert_eq!(
+            render(&results(), Format::Text),
+            "Day 1 - Part 1: 7 (FAIL, expected 8, 12.0µs)\nDay 1 - Part 2: error: Invalid input: \"deep\", line 2\n"
+        );
+    }
+
+    #[test]
+    fn test_render_json() {
+        assert_eq!(
+            render(&results(), Format::Json),
+            concat!(
+                "[\n",
+                "  {\"year\": 2021, \"day\": 1, \"part\": 1, \"answer\": \"7\", \"answer_type\": \"signed\", ",
+                "\"status\": \"fail\", \"expected\": \"8\", \"duration_ns\": 12000, \"input_hash\": \"00ff\", ",
+                "\"error\": null},\n",
+                "  {\"year\": 2021, \"day\": 1, \"part\": 2, \"answer\": null, \"answer_type\": null, ",
+                "\"status\": \"error\", \"expected\": null, \"duration_ns\": 500, \"input_hash\": \"00ff\", ",
+                "\"error\": \"Invalid input: \\\"deep\\\", line 2\"}\n",
+                "]\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_render_csv() {
+        assert_eq!(
+            render(&results(), Format::Csv),
+            concat!(
+                "year,day,part,answer,answer_type,status,expected,duration_ns,input_hash,error\n",
+                "2021,1,1,7,signed,fail,8,12000,00ff,\n",
+                "2021,1,2,,,error,,500,00ff,\"Invalid input: \"\"deep\"\", line 2\"\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(json_string("a\"b\\c\nd\u{1}"), "\"a\\\"b\\\\c\\nd\\u0001\"");
+    }
+
+    #[test]
+    fn test_solve() {
+        let puzzle = crate::find(2021, 1).unwrap();
+        let results = solve(
+            puzzle,
+            "199\n200\n208\n210\n200\n207\n",
+            &Answers::default(),
+        );
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].answer, Ok(Answer::Unsigned(4)));
+        assert_eq!(results[0].status(), "unknown");
+        assert_eq!(results[1].part, 2);
+
+        let puzzle = crate::find(2021, 3).unwrap();
+        let results = solve(puzzle, "00100\n0110\n", &Answers::default());
+        assert_eq!(results[0].status(), "error");
+        assert_eq!(results[1].status(), "error");
+    }
+}
@@ -0,0 +1,120 @@
+//! Generating the directory for a new day from `src/day.rs.template`.
+
+use std::error;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::Result;
+
+const TEMPLATE: &str = include_str!("day.rs.template");
+
+#[derive(Debug, PartialEq)]
+pub struct ScaffoldError(String);
+
+impl fmt::Display for ScaffoldError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+impl error::Error for ScaffoldError {}
+
+/// The module source for a day, with the template's names filled in
+pub fn render(year: u16, day: u8) -> String {
+    TEMPLATE
+        .replace("{{year}}", &year.to_string())
+        .replace("{{day}}", &day.to_string())
+}
+
+/// Create `y<year>/day<day>` under `src` with the module and an empty example. `build.rs` picks
+/// the new module up on the next build, and the input comes from the input store. Existing days
+/// are never overwritten.
+pub fn new_day(src: &Path, year: u16, day: u8) -> Result<PathBuf> {
+    if !(1..=25).contains(&day) {
+        return Err(Box::new(ScaffoldError(format!(
+            "Day must be between 1 and 25, got {}",
+            day
+        ))));
+    }
+
+    let dir = src.join(format!("y{}", year)).join(format!("day{}", day));
+    if dir.exists() {
+        return Err(Box::new(ScaffoldError(format!(
+            "{} day {} already exists at {}",
+            year,
+            day,
+            dir.display()
+        ))));
+    }
+
+    fs::create_dir_all(&dir)?;
+    fs::write(dir.join("mod.rs"), render(year, day))?;
+    fs::write(dir.join("example.txt"), "")?;
+
+    Ok(dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn temp_src(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("aoc-scaffold-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_render() {
+        let source = render(2022, 7);
+
+        assert!(source.contains("struct Day7Error(String);"));
+        assert!(source.contains("crate::answers::real_input(2022, 7, 1)"));
+        assert_eq!(
+            source.matches("#[ignore = \"example.txt is empty").count(),
+            2
+        );
+        assert!(!source.contains("{{"));
+    }
+
+    #[test]
+    fn test_new_day() {
+        let src = temp_src("new");
+        let dir = new_day(&src, 2022, 1).unwrap();
+
+        assert_eq!(dir, src.join("y2022").join("day1"));
+        assert_eq!(
+            fs::read_to_string(dir.join("mod.rs")).unwrap(),
+            render(2022, 1)
+        );
+        assert!(!dir.join("input.txt").exists());
+        assert_eq!(fs::read_to_string(dir.join("example.txt")).unwrap(), "");
+
+        fs::remove_dir_all(src).unwrap();
+    }
+
+    #[test]
+    fn test_new_day_refuses_to_overwrite() {
+        let src = temp_src("overwrite");
+        let dir = new_day(&src, 2022, 2).unwrap();
+        fs::write(dir.join("example.txt"), "199\n200\n").unwrap();
+
+        assert!(new_day(&src, 2022, 2).is_err());
+        assert_eq!(
+            fs::read_to_string(dir.join("example.txt")).unwrap(),
+            "199\n200\n"
+        );
+
+        fs::remove_dir_all(src).unwrap();
+    }
+
+    #[test]
+    fn test_new_day_invalid() {
+        let src = temp_src("invalid");
+
+        assert!(new_day(&src, 2022, 0).is_err());
+        assert!(new_day(&src, 2022, 26).is_err());
+        assert!(!src.exists());
+    }
+}
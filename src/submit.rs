@@ -0,0 +1,549 @@
+//! Submitting answers to the site, keeping a history of every submission so an answer that's
+//! known to be wrong, or outside the bounds of earlier "too high" and "too low" answers, is never
+//! sent again, and the cooldown after a wrong answer is waited out locally.
+//!
+//! The history is a plain text file with a line per submission:
+//!
+//! ```text
+//! # year day part unix-time outcome answer
+//! 2021 3 2 1638519342 high 4012345
+//! ```
+
+use std::error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::inputs::USER_AGENT;
+use crate::Result;
+
+const BASE_URL: &str = "https://adventofcode.com";
+
+/// How long the site makes you wait after a wrong answer, at least
+const COOLDOWN_SECS: u64 = 60;
+
+#[derive(Debug, PartialEq)]
+pub struct SubmitError(String);
+
+impl fmt::Display for SubmitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+impl error::Error for SubmitError {}
+
+/// What the site said about a submitted answer
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Outcome {
+    Correct,
+    Wrong,
+    TooHigh,
+    TooLow,
+    /// Submitted too soon after the last one, with this many seconds left to wait
+    Wait(u64),
+    /// The part was already solved, so the answer wasn't checked
+    AlreadySolved,
+}
+
+impl Outcome {
+    fn is_rejection(self) -> bool {
+        matches!(self, Outcome::Wrong | Outcome::TooHigh | Outcome::TooLow)
+    }
+
+    fn parse(text: &str) -> Option<Outcome> {
+        match text {
+            "correct" => Some(Outcome::Correct),
+            "wrong" => Some(Outcome::Wrong),
+            "high" => Some(Outcome::TooHigh),
+            "low" => Some(Outcome::TooLow),
+            "solved" => Some(Outcome::AlreadySolved),
+            _ => text
+                .strip_prefix("wait:")
+                .and_then(|secs| secs.parse().ok())
+                .map(Outcome::Wait),
+        }
+    }
+
+    /// How it's written in the history
+    fn key(self) -> String {
+        match self {
+            Outcome::Correct => "correct".into(),
+            Outcome::Wrong => "wrong".into(),
+            Outcome::TooHigh => "high".into(),
+            Outcome::TooLow => "low".into(),
+            Outcome::Wait(secs) => format!("wait:{}", secs),
+            Outcome::AlreadySolved => "solved".into(),
+        }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Outcome::Correct => write!(f, "correct"),
+            Outcome::Wrong => write!(f, "wrong"),
+            Outcome::TooHigh => write!(f, "too high"),
+            Outcome::TooLow => write!(f, "too low"),
+            Outcome::Wait(secs) => write!(f, "too soon, {}s left to wait", secs),
+            Outcome::AlreadySolved => write!(f, "already solved"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Submission {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    /// When it was submitted, in seconds since the Unix epoch
+    pub time: u64,
+    pub outcome: Outcome,
+    pub answer: String,
+}
+
+/// Every answer submitted, oldest first
+#[derive(Debug, Default, PartialEq)]
+pub struct History {
+    submissions: Vec<Submission>,
+}
+
+impl History {
+    /// Load the history at `path`, a missing file being an empty history
+    pub fn load(path: &Path) -> Result<History> {
+        match fs::read_to_string(path) {
+            Ok(text) => History::parse(&text),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(History::default()),
+            Err(e) => Err(Box::new(e)),
+        }
+    }
+
+    pub fn parse(text: &str) -> Result<History> {
+        let mut history = History::default();
+
+        for (idx, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = || {
+                SubmitError(format!(
+                    "Invalid history line {}, expected <year> <day> <part> <time> <outcome> <answer>: {}",
+                    idx + 1,
+                    line
+                ))
+            };
+
+            let fields = line.splitn(6, ' ').collect::<Vec<&str>>();
+            if fields.len() != 6 {
+                return Err(Box::new(invalid()));
+            }
+            history.record(Submission {
+                year: fields[0].parse().map_err(|_| invalid())?,
+                day: fields[1].parse().map_err(|_| invalid())?,
+                part: fields[2].parse().map_err(|_| invalid())?,
+                time: fields[3].parse().map_err(|_| invalid())?,
+                outcome: Outcome::parse(fields[4]).ok_or_else(invalid)?,
+                answer: fields[5].into(),
+            });
+        }
+
+        Ok(history)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        Ok(fs::write(path, self.to_string())?)
+    }
+
+    pub fn record(&mut self, submission: Submission) {
+        self.submissions.push(submission);
+    }
+
+    /// Why `answer` shouldn't be submitted for a part at `now`, if there's a reason not to
+    pub fn check(&self, year: u16, day: u8, part: u8, answer: &str, now: u64) -> Option<String> {
+        let submissions = self
+            .submissions
+            .iter()
+            .filter(|submission| submission.year == year && submission.day == day)
+            .collect::<Vec<&Submission>>();
+        let for_part = submissions
+            .iter()
+            .filter(|submission| submission.part == part);
+
+        if let Some(correct) = for_part
+            .clone()
+            .find(|submission| submission.outcome == Outcome::Correct)
+        {
+            return Some(format!(
+                "Part {} was already solved with {}",
+                part, correct.answer
+            ));
+        }
+        if let Some(rejected) = for_part
+            .clone()
+            .find(|submission| submission.outcome.is_rejection() && submission.answer == answer)
+        {
+            return Some(format!(
+                "{} was already rejected as {}",
+                answer, rejected.outcome
+            ));
+        }
+
+        // Bounds only make sense for numbers
+        if let Ok(value) = answer.parse::<i128>() {
+            let bound = |outcome| {
+                for_part
+                    .clone()
+                    .filter(move |submission| submission.outcome == outcome)
+                    .filter_map(|submission| submission.answer.parse::<i128>().ok())
+            };
+            if let Some(high) = bound(Outcome::TooHigh).min().filter(|&high| value >= high) {
+                return Some(format!("{} is too high, as {} already was", value, high));
+            }
+            if let Some(low) = bound(Outcome::TooLow).max().filter(|&low| value <= low) {
+                return Some(format!("{} is too low, as {} already was", value, low));
+            }
+        }
+
+        let until = submissions.last().and_then(|last| match last.outcome {
+            Outcome::Wait(secs) => Some(last.time.saturating_add(secs)),
+            outcome if outcome.is_rejection() => Some(last.time.saturating_add(COOLDOWN_SECS)),
+            _ => None,
+        });
+        match until {
+            Some(until) if now < until => Some(format!(
+                "Wait {}s before submitting again for {} day {}",
+                until - now,
+                year,
+                day
+            )),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for History {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "# year day part unix-time outcome answer")?;
+        for submission in &self.submissions {
+            writeln!(
+                f,
+                "{} {} {} {} {} {}",
+                submission.year,
+                submission.day,
+                submission.part,
+                submission.time,
+                submission.outcome.key(),
+                submission.answer
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Somewhere to submit answers to
+pub trait SubmitClient {
+    fn submit(&self, year: u16, day: u8, part: u8, answer: &str) -> Result<Outcome>;
+}
+
+/// Posts answers to the Advent of Code site, logged in with a session cookie
+pub struct HttpClient {
+    base_url: String,
+    session: String,
+}
+
+impl HttpClient {
+    pub fn new(session: &str) -> HttpClient {
+        HttpClient::with_base_url(BASE_URL, session)
+    }
+
+    /// Submit to another server, like a local stand-in for the site
+    pub fn with_base_url(base_url: &str, session: &str) -> HttpClient {
+        HttpClient {
+            base_url: base_url.trim_end_matches('/').into(),
+            session: session.into(),
+        }
+    }
+}
+
+impl SubmitClient for HttpClient {
+    fn submit(&self, year: u16, day: u8, part: u8, answer: &str) -> Result<Outcome> {
+        let url = format!("{}/{}/day/{}/answer", self.base_url, year, day);
+        let response = ureq::post(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .set("User-Agent", USER_AGENT)
+            .send_form(&[("level", &part.to_string()), ("answer", answer)]);
+
+        match response {
+            Ok(response) => parse_response(&response.into_string()?),
+            Err(ureq::Error::Status(status, _)) => Err(Box::new(SubmitError(format!(
+                "Submitting to {} failed with status {}, check AOC_SESSION",
+                url, status
+            )))),
+            Err(e) => Err(Box::new(e)),
+        }
+    }
+}
+
+/// Read the outcome from the page the site replies with
+fn parse_response(html: &str) -> Result<Outcome> {
+    if html.contains("That's the right answer") {
+        Ok(Outcome::Correct)
+    } else if html.contains("That's not the right answer") {
+        if html.contains("your answer is too high") {
+            Ok(Outcome::TooHigh)
+        } else if html.contains("your answer is too low") {
+            Ok(Outcome::TooLow)
+        } else {
+            Ok(Outcome::Wrong)
+        }
+    } else if html.contains("You gave an answer too recently") {
+        Ok(Outcome::Wait(parse_wait(html).unwrap_or(COOLDOWN_SECS)))
+    } else if html.contains("Did you already complete it?") {
+        Ok(Outcome::AlreadySolved)
+    } else {
+        Err(Box::new(SubmitError(
+            "Couldn't make sense of the site's reply".into(),
+        )))
+    }
+}
+
+/// The seconds in "You have 1m 5s left to wait"
+fn parse_wait(html: &str) -> Option<u64> {
+    let (before, _) = html.split_once(" left to wait")?;
+    let (_, wait) = before.rsplit_once("You have ")?;
+
+    // A wait too long to count is as good as forever, so it saturates rather than overflowing
+    wait.split_whitespace().try_fold(0u64, |total, amount| {
+        let secs = if let Some(minutes) = amount.strip_suffix('m') {
+            minutes.parse::<u64>().ok()?.saturating_mul(60)
+        } else {
+            amount.strip_suffix('s')?.parse::<u64>().ok()?
+        };
+        Some(total.saturating_add(secs))
+    })
+}
+
+/// Submit an answer unless the history says not to, recording what the site said
+pub fn submit(
+    client: &dyn SubmitClient,
+    history: &mut History,
+    year: u16,
+    day: u8,
+    part: u8,
+    answer: &str,
+    now: u64,
+) -> Result<Outcome> {
+    // The history keeps one submission per line, with the answer last
+    if answer.is_empty() || answer.trim() != answer || answer.contains(char::is_control) {
+        return Err(Box::new(SubmitError(format!(
+            "Not submitting {:?}, answers have to be a single line without surrounding spaces",
+            answer
+        ))));
+    }
+    if let Some(reason) = history.check(year, day, part, answer, now) {
+        return Err(Box::new(SubmitError(format!("Not submitting: {}", reason))));
+    }
+
+    let outcome = client.submit(year, day, part, answer)?;
+    history.record(Submission {
+        year,
+        day,
+        part,
+        time: now,
+        outcome,
+        answer: answer.into(),
+    });
+
+    Ok(outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_server::MockServer;
+
+    fn submission(part: u8, time: u64, outcome: Outcome, answer: &str) -> Submission {
+        Submission {
+            year: 2021,
+            day: 3,
+            part,
+            time,
+            outcome,
+            answer: answer.into(),
+        }
+    }
+
+    fn page(message: &str) -> String {
+        format!(
+            "<html><body><main><article><p>{}</p></article></main></body></html>",
+            message
+        )
+    }
+
+    #[test]
+    fn test_parse_response() {
+        let cases = [
+            ("That's the right answer!  You are one gold star closer.", Outcome::Correct),
+            ("That's not the right answer.  If you're stuck, make sure...", Outcome::Wrong),
+            ("That's not the right answer; your answer is too high.", Outcome::TooHigh),
+            ("That's not the right answer; your answer is too low.", Outcome::TooLow),
+            (
+                "You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 1m 5s left to wait.",
+                Outcome::Wait(65),
+            ),
+            (
+                "You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 42s left to wait.",
+                Outcome::Wait(42),
+            ),
+            (
+                "You don't seem to be solving the right level.  Did you already complete it?",
+                Outcome::AlreadySolved,
+            ),
+        ];
+
+        for (message, outcome) in cases {
+            assert_eq!(
+                parse_response(&page(message)).unwrap(),
+                outcome,
+                "{}",
+                message
+            );
+        }
+        assert!(parse_response(&page("Puzzle inputs differ by user.")).is_err());
+    }
+
+    #[test]
+    fn test_parse_wait() {
+        assert_eq!(parse_wait("You have 2m left to wait."), Some(120));
+        assert_eq!(parse_wait("You have 1m 5é left to wait."), None);
+        assert_eq!(parse_wait("You have ⏳ left to wait."), None);
+        assert_eq!(parse_wait("You have 5 left to wait."), None);
+        assert_eq!(parse_wait("Please wait."), None);
+        assert_eq!(
+            parse_wait("You have 307445734561825862m left to wait."),
+            Some(u64::MAX)
+        );
+        assert_eq!(
+            parse_wait("You have 1m 18446744073709551615s left to wait."),
+            Some(u64::MAX)
+        );
+    }
+
+    #[test]
+    fn test_history_round_trip() {
+        let mut history = History::default();
+        history.record(submission(1, 100, Outcome::TooHigh, "4012345"));
+        history.record(submission(1, 200, Outcome::Wait(30), "12"));
+        history.record(submission(2, 300, Outcome::Correct, "a b"));
+
+        assert_eq!(History::parse(&history.to_string()).unwrap(), history);
+        assert!(History::parse("2021 3 1 100 high").is_err());
+        assert!(History::parse("2021 3 1 100 maybe 12").is_err());
+        assert!(History::parse("# comment\n\n2021 3 1 100 wait:5 12\n").is_ok());
+    }
+
+    #[test]
+    fn test_check() {
+        let mut history = History::default();
+        history.record(submission(1, 100, Outcome::TooHigh, "500"));
+        history.record(submission(1, 200, Outcome::TooLow, "100"));
+        history.record(submission(1, 300, Outcome::Wrong, "250"));
+
+        let check = |answer| history.check(2021, 3, 1, answer, 1000);
+        assert_eq!(check("250").unwrap(), "250 was already rejected as wrong");
+        assert_eq!(
+            check("500").unwrap(),
+            "500 was already rejected as too high"
+        );
+        assert_eq!(check("600").unwrap(), "600 is too high, as 500 already was");
+        assert_eq!(check("50").unwrap(), "50 is too low, as 100 already was");
+        assert_eq!(check("300"), None);
+        assert_eq!(check("ABC"), None);
+        assert_eq!(history.check(2021, 3, 2, "600", 1000), None);
+        assert_eq!(history.check(2021, 4, 1, "600", 1000), None);
+    }
+
+    #[test]
+    fn test_check_cooldown() {
+        let mut history = History::default();
+        history.record(submission(1, 100, Outcome::Wrong, "250"));
+
+        assert_eq!(
+            history.check(2021, 3, 1, "300", 130).unwrap(),
+            "Wait 30s before submitting again for 2021 day 3"
+        );
+        assert_eq!(
+            history.check(2021, 3, 2, "300", 130).unwrap(),
+            "Wait 30s before submitting again for 2021 day 3"
+        );
+        assert_eq!(history.check(2021, 3, 1, "300", 160), None);
+
+        history.record(submission(1, 200, Outcome::Wait(300), "300"));
+        assert!(history.check(2021, 3, 1, "300", 499).is_some());
+        assert_eq!(history.check(2021, 3, 1, "300", 500), None);
+
+        history.record(submission(2, 400, Outcome::Wait(u64::MAX), "1"));
+        assert!(history.check(2021, 3, 2, "2", u64::MAX - 1).is_some());
+
+        history.record(submission(1, 600, Outcome::Correct, "300"));
+        assert_eq!(
+            history.check(2021, 3, 1, "300", 700).unwrap(),
+            "Part 1 was already solved with 300"
+        );
+        assert_eq!(history.check(2021, 3, 2, "1", 600), None);
+    }
+
+    #[test]
+    fn test_submit() {
+        let site = MockServer::start(|request| match request.body.as_str() {
+            "level=1&answer=198" => (200, page("That's the right answer!")),
+            _ => (
+                200,
+                page("That's not the right answer; your answer is too low."),
+            ),
+        });
+        let client = HttpClient::with_base_url(site.url(), "secret");
+        let mut history = History::default();
+
+        let outcome = submit(&client, &mut history, 2021, 3, 1, "198", 100).unwrap();
+        assert_eq!(outcome, Outcome::Correct);
+        let request = &site.requests()[0];
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/2021/day/3/answer");
+        assert_eq!(request.header("Cookie"), Some("session=secret"));
+
+        let outcome = submit(&client, &mut history, 2021, 3, 2, "5", 200).unwrap();
+        assert_eq!(outcome, Outcome::TooLow);
+        assert_eq!(
+            history.submissions,
+            vec![
+                submission(1, 100, Outcome::Correct, "198"),
+                submission(2, 200, Outcome::TooLow, "5")
+            ]
+        );
+
+        // Refused locally, so the site never sees these
+        assert!(submit(&client, &mut history, 2021, 3, 1, "198", 300).is_err());
+        assert!(submit(&client, &mut history, 2021, 3, 2, "4", 300).is_err());
+        assert!(submit(&client, &mut history, 2021, 3, 2, "6", 210).is_err());
+        for answer in ["", "6\n2021 3 2 0 right 6", " 6", "6\t"] {
+            assert!(submit(&client, &mut history, 2021, 3, 2, answer, 400).is_err());
+        }
+        assert_eq!(site.requests().len(), 2);
+        assert_eq!(history.submissions.len(), 2);
+    }
+
+    #[test]
+    fn test_submit_http_error() {
+        let site = MockServer::start(|_| (500, "Internal Server Error".into()));
+        let client = HttpClient::with_base_url(site.url(), "expired");
+        let mut history = History::default();
+
+        assert!(submit(&client, &mut history, 2021, 3, 1, "198", 100).is_err());
+        assert_eq!(history, History::default());
+    }
+}
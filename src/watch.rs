@@ -0,0 +1,117 @@
+//! Polling files for changes and describing how the answers changed between runs, for
+//! `aoc watch`. Polling keeps it to the standard library and works the same everywhere.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::report::PartResult;
+
+/// What a file looked like when it was last polled, `None` when it didn't exist. The length is
+/// kept too, as modification times can be too coarse to see quick saves.
+type Stamp = Option<(Option<SystemTime>, u64)>;
+
+fn stamp(path: &Path) -> Stamp {
+    fs::metadata(path)
+        .ok()
+        .map(|metadata| (metadata.modified().ok(), metadata.len()))
+}
+
+pub struct Watcher {
+    files: Vec<(PathBuf, Stamp)>,
+}
+
+impl Watcher {
+    /// Watch `paths`, taking how they look now as unchanged
+    pub fn new(paths: &[PathBuf]) -> Watcher {
+        Watcher {
+            files: paths
+                .iter()
+                .map(|path| (path.clone(), stamp(path)))
+                .collect(),
+        }
+    }
+
+    /// The files that changed since the last poll, including ones that appeared or went away
+    pub fn poll(&mut self) -> Vec<PathBuf> {
+        let mut changed = vec![];
+
+        for (path, last) in self.files.iter_mut() {
+            let current = stamp(path);
+            if current != *last {
+                *last = current;
+                changed.push(path.clone());
+            }
+        }
+
+        changed
+    }
+}
+
+/// The results of a run, one line per part, noting answers that differ from the previous run
+pub fn render(name: &str, previous: &[PartResult], results: &[PartResult]) -> String {
+    results
+        .iter()
+        .map(|result| {
+            let answer = match &result.answer {
+                Ok(answer) => format!("{} ({}, {:.1?})", answer, result.check, result.duration),
+                Err(e) => format!("error: {}", e),
+            };
+            let change = match previous.iter().find(|last| last.part == result.part) {
+                Some(last) if last.answer != result.answer => match &last.answer {
+                    Ok(answer) => format!(", was {}", answer),
+                    Err(_) => ", was an error".into(),
+                },
+                _ => String::new(),
+            };
+            format!(
+                "{:<8} Day {} - Part {}: {}{}\n",
+                name, result.day, result.part, answer, change
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::answers::Answers;
+    use std::env;
+
+    #[test]
+    fn test_poll() {
+        let path = env::temp_dir().join(format!("aoc-watch-{}.txt", std::process::id()));
+        fs::write(&path, "199\n").unwrap();
+        let mut watcher = Watcher::new(std::slice::from_ref(&path));
+
+        assert!(watcher.poll().is_empty());
+        fs::write(&path, "199\n200\n").unwrap();
+        assert_eq!(watcher.poll(), vec![path.clone()]);
+        assert!(watcher.poll().is_empty());
+
+        fs::remove_file(&path).unwrap();
+        assert_eq!(watcher.poll(), vec![path.clone()]);
+        assert!(watcher.poll().is_empty());
+    }
+
+    #[test]
+    fn test_render() {
+        let puzzle = crate::find(2021, 1).unwrap();
+        let first = crate::report::solve(puzzle, "199\n200\n208\n210\n", &Answers::default());
+        let second = crate::report::solve(puzzle, "199\n200\n198\n210\n", &Answers::default());
+        let broken = crate::report::solve(puzzle, "199\ndeep\n", &Answers::default());
+
+        let rendered = render("example", &first, &second);
+        let lines = rendered.lines().collect::<Vec<&str>>();
+        assert!(lines[0].starts_with("example  Day 1 - Part 1: 2 (unknown, "));
+        assert!(lines[0].ends_with(", was 3"));
+        assert!(lines[1].starts_with("example  Day 1 - Part 2: 1 (unknown, "));
+        assert!(lines[1].ends_with("s)"));
+
+        assert!(render("example", &[], &first)
+            .lines()
+            .all(|line| !line.contains("was")));
+        assert!(render("example", &broken, &first).contains(", was an error\n"));
+        assert!(render("example", &first, &broken).contains("Part 1: error: "));
+    }
+}
@@ -0,0 +1,213 @@
+use std::error;
+use std::fmt;
+
+use crate::{Answer, Command, Solver};
+
+type Result<T> = std::result::Result<T, Box<dyn error::Error>>;
+
+#[derive(Debug, PartialEq)]
+struct Day1Error(String);
+
+impl fmt::Display for Day1Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid input: {}", self.0)
+    }
+}
+impl error::Error for Day1Error {}
+
+pub struct Day;
+
+impl Solver for Day {
+    fn parse(&self, input: &str) -> Result<()> {
+        parse_depths(input.split('\n'))?;
+        Ok(())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer> {
+        Ok(part1(input)?.into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer> {
+        Ok(part2(input)?.into())
+    }
+
+    fn commands(&self) -> &'static [Command] {
+        &[Command {
+            name: "window",
+            usage: "<size>",
+            run: window,
+        }]
+    }
+}
+
+/// Count the increases with a sliding window of any size
+fn window(args: &[String], input: &str) -> Result<()> {
+    let window_size = match args.first().map(|size| size.parse::<usize>()) {
+        Some(Ok(size)) if size > 0 => size,
+        _ => return Err(Box::new(Day1Error("Expected a window size of at least 1".into()))),
+    };
+
+    println!(
+        "Increases with a window of {}: {}",
+        window_size,
+        count_increases_window(input.split('\n'), window_size)?
+    );
+
+    Ok(())
+}
+
+fn part1(input: &str) -> Result<usize> {
+    let lines = input.split('\n');
+
+    count_increases(lines)
+}
+
+fn part2(input: &str) -> Result<usize> {
+    let lines = input.split('\n');
+
+    count_increases_window(lines, 3)
+}
+
+/// The depth on each non-empty line, parsed the way both parts do
+fn parse_depths<'a>(lines: impl Iterator<Item = &'a str>) -> Result<Vec<i32>> {
+    Ok(lines
+        .filter(|line| !line.is_empty())
+        .map(|line| line.parse::<i32>())
+        .collect::<std::result::Result<Vec<i32>, _>>()?)
+}
+
+fn count_increases<'a>(lines: impl Iterator<Item = &'a str>) -> Result<usize> {
+    count_increases_window(lines, 1)
+}
+
+fn count_increases_window<'a>(
+    lines: impl Iterator<Item = &'a str>,
+    window_size: usize,
+) -> Result<usize> {
+    let depths = parse_depths(lines)?;
+
+    // Neighbouring windows share everything but their earliest and latest depths, so comparing
+    // those is the same as comparing the sums, without any chance of overflowing
+    Ok(depths
+        .iter()
+        .zip(depths.iter().skip(window_size))
+        .filter(|(earliest, latest)| latest > earliest)
+        .count())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const EXAMPLE: &str = include_str!("example.txt");
+
+    #[test]
+    fn test_count_increases() {
+        let input = vec!["10", "11", "9", "12"];
+
+        assert_eq!(count_increases(input.into_iter()).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_count_increases_empty() {
+        let input = vec![];
+
+        assert_eq!(count_increases(input.into_iter()).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_count_increases_handles_empty_lines() {
+        let input = vec!["10", "", "11", "9", "12", ""];
+
+        assert_eq!(count_increases(input.into_iter()).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_solver_parse_matches_parts() {
+        for input in ["\n10\n11\n", "10\n\n11\n12\n13\n", "10\r\n11\r\n", "10\nx\n"] {
+            assert_eq!(
+                Day.parse(input).is_ok(),
+                part1(input).is_ok(),
+                "{:?}",
+                input
+            );
+            assert_eq!(
+                Day.parse(input).is_ok(),
+                part2(input).is_ok(),
+                "{:?}",
+                input
+            );
+        }
+        assert_eq!(part1("\n10\n11\n").unwrap(), 1);
+    }
+
+    #[test]
+    fn test_count_increases_window() {
+        let input = vec![
+            "199", "200", "208", "210", "200", "207", "240", "269", "260", "263",
+        ];
+
+        assert_eq!(count_increases_window(input.into_iter(), 3).unwrap(), 5);
+    }
+
+    #[test]
+    fn test_part1_example() {
+        assert_eq!(part1(EXAMPLE).unwrap(), 7);
+    }
+
+    #[test]
+    fn test_part2_example() {
+        assert_eq!(part2(EXAMPLE).unwrap(), 5);
+    }
+
+    #[test]
+    #[cfg_attr(not(has_answer = "2021-1-1"), ignore = "no input.txt with an answer")]
+    fn test_part1() {
+        let (input, expected) = crate::answers::real_input(2021, 1, 1);
+        assert_eq!(part1(&input).unwrap().to_string(), expected);
+    }
+
+    #[test]
+    #[cfg_attr(not(has_answer = "2021-1-2"), ignore = "no input.txt with an answer")]
+    fn test_part2() {
+        let (input, expected) = crate::answers::real_input(2021, 1, 2);
+        assert_eq!(part2(&input).unwrap().to_string(), expected);
+    }
+
+    fn lines(depths: &[i32]) -> Vec<String> {
+        depths.iter().map(|depth| depth.to_string()).collect()
+    }
+
+    proptest! {
+        #[test]
+        fn prop_window_of_one_counts_increases(depths in prop::collection::vec(-10000..10000, 0..50)) {
+            let lines = lines(&depths);
+            let lines = lines.iter().map(String::as_str);
+
+            prop_assert_eq!(
+                count_increases(lines.clone()).unwrap(),
+                count_increases_window(lines, 1).unwrap()
+            );
+        }
+
+        #[test]
+        fn prop_window_compares_depths_a_window_apart(
+            depths in prop::collection::vec(-10000..10000, 0..50),
+            window_size in 1..5usize,
+        ) {
+            let lines = lines(&depths);
+            // The shared depths of two windows cancel out
+            let expected = depths
+                .iter()
+                .zip(depths.iter().skip(window_size))
+                .filter(|(earliest, latest)| latest > earliest)
+                .count();
+
+            prop_assert_eq!(
+                count_increases_window(lines.iter().map(String::as_str), window_size).unwrap(),
+                expected
+            );
+        }
+    }
+}
@@ -0,0 +1,1118 @@
+use std::error;
+use std::fmt;
+
+use crate::{Answer, Command, Solver};
+
+type Result<T> = std::result::Result<T, Box<dyn error::Error>>;
+
+#[derive(Debug, PartialEq)]
+struct Day2Error(String);
+
+impl fmt::Display for Day2Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid input: {}", self.0)
+    }
+}
+impl error::Error for Day2Error {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Forward,
+    Down,
+    Up,
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Movement {
+    distance: i32,
+    direction: Direction,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Position {
+    horizontal: i32,
+    depth: i32,
+}
+
+/// A position that can also move sideways, with positive `lateral` values to the right
+#[derive(Debug, PartialEq, Eq)]
+struct Position3d {
+    horizontal: i32,
+    lateral: i32,
+    depth: i32,
+}
+
+impl Position3d {
+    fn manhattan_distance(&self) -> i64 {
+        [self.horizontal, self.lateral, self.depth]
+            .iter()
+            .map(|val| (*val as i64).abs())
+            .sum()
+    }
+
+    fn euclidean_distance(&self) -> f64 {
+        [self.horizontal, self.lateral, self.depth]
+            .iter()
+            .map(|val| (*val as f64).powi(2))
+            .sum::<f64>()
+            .sqrt()
+    }
+}
+
+/// The two ways of interpreting the commands, from part 1 and part 2 respectively
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Model {
+    /// `down` and `up` change the depth directly, and `left` and `right` the lateral position
+    Basic,
+    /// `down` and `up` change the aim (pitch), `left` and `right` change the heading (yaw), and
+    /// `forward` moves along both
+    Aim,
+}
+
+/// How forgiving to be when parsing a command file. `parse_movement` itself sits between the two,
+/// as it accepts empty lines, trailing input and negative distances, but nothing else.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ParseMode {
+    /// Exactly `<direction> <distance>`, lowercase, single space, non-negative distance and
+    /// nothing else on the line
+    Strict,
+    /// Directions in any case, any amount of whitespace, blank lines and `#` comments
+    Lenient,
+}
+
+impl fmt::Display for Movement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let direction = match self.direction {
+            Direction::Forward => "forward",
+            Direction::Down => "down",
+            Direction::Up => "up",
+            Direction::Left => "left",
+            Direction::Right => "right",
+        };
+        write!(f, "{} {}", direction, self.distance)
+    }
+}
+
+pub struct Day;
+
+impl Solver for Day {
+    fn parse(&self, input: &str) -> Result<()> {
+        parse_route(input.split('\n'))?;
+        Ok(())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer> {
+        Ok(part1(input)?.into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer> {
+        Ok(part2(input)?.into())
+    }
+
+    fn commands(&self) -> &'static [Command] {
+        &[
+            Command {
+                name: "optimise",
+                usage: "<basic|aim>",
+                run: optimise,
+            },
+            Command {
+                name: "3d",
+                usage: "<basic|aim>",
+                run: navigate_3d,
+            },
+            Command {
+                name: "strict",
+                usage: "",
+                run: solve_strict,
+            },
+            Command {
+                name: "lenient",
+                usage: "",
+                run: solve_lenient,
+            },
+        ]
+    }
+}
+
+fn parse_model(args: &[String]) -> Result<Model> {
+    match args.first().map(String::as_str) {
+        Some("basic") => Ok(Model::Basic),
+        Some("aim") => Ok(Model::Aim),
+        _ => Err(Box::new(Day2Error("Expected a model, basic or aim".into()))),
+    }
+}
+
+/// Print a shorter, equivalent version of the command file
+fn optimise(args: &[String], input: &str) -> Result<()> {
+    let model = parse_model(args)?;
+    let lines = input.split('\n');
+
+    let original_count = lines.clone().filter(|line| !line.is_empty()).count();
+    let route = optimise_route(lines, model)?;
+
+    for movement in route.iter() {
+        println!("{}", movement);
+    }
+    eprintln!(
+        "Optimised {} commands down to {}",
+        original_count,
+        route.len()
+    );
+
+    Ok(())
+}
+
+fn solve_strict(_args: &[String], input: &str) -> Result<()> {
+    solve_with_mode(input, ParseMode::Strict)
+}
+
+fn solve_lenient(_args: &[String], input: &str) -> Result<()> {
+    solve_with_mode(input, ParseMode::Lenient)
+}
+
+/// Solve both parts, parsing the input with the given mode rather than with `parse_movement`
+fn solve_with_mode(input: &str, mode: ParseMode) -> Result<()> {
+    let movements = parse_commands(input.split('\n'), mode)?;
+
+    let position = follow_route(&movements, Model::Basic)?;
+    println!("Day 2 - Part 1: {}", answer(&position)?);
+    let position = follow_route(&movements, Model::Aim)?;
+    println!("Day 2 - Part 2: {}", answer(&position)?);
+
+    Ok(())
+}
+
+/// Follow the command file in 3D and print where the submarine ends up
+fn navigate_3d(args: &[String], input: &str) -> Result<()> {
+    let model = parse_model(args)?;
+    let movements = input
+        .split('\n')
+        .map(parse_movement)
+        .collect::<Result<Vec<Movement>>>()?;
+
+    let position = follow_route_3d(&movements, model)?;
+    println!(
+        "Position: horizontal {}, lateral {}, depth {}",
+        position.horizontal, position.lateral, position.depth
+    );
+    println!("Manhattan distance: {}", position.manhattan_distance());
+    println!("Euclidean distance: {:.3}", position.euclidean_distance());
+
+    Ok(())
+}
+
+fn part1(input: &str) -> Result<i32> {
+    let lines = input.split('\n');
+
+    let position = parse_movements(lines)?;
+
+    answer(&position)
+}
+
+fn part2(input: &str) -> Result<i32> {
+    let lines = input.split('\n');
+
+    let position = parse_movements_with_aim(lines)?;
+
+    answer(&position)
+}
+
+/// Parse the lines of the input the way both parts do
+fn parse_route<'a>(lines: impl Iterator<Item = &'a str>) -> Result<Vec<Movement>> {
+    lines.map(parse_movement).collect()
+}
+
+fn parse_movements<'a>(lines: impl Iterator<Item = &'a str>) -> Result<Position> {
+    let movements = parse_route(lines)?;
+
+    follow_route(&movements, Model::Basic)
+}
+
+fn parse_movements_with_aim<'a>(lines: impl Iterator<Item = &'a str>) -> Result<Position> {
+    let movements = parse_route(lines)?;
+
+    follow_route(&movements, Model::Aim)
+}
+
+/// Follow a list of already parsed movements from the origin under the given model, in the plane
+/// the puzzle describes. Lateral movements are not allowed here, use `follow_route_3d` for those.
+fn follow_route(movements: &[Movement], model: Model) -> Result<Position> {
+    if let Some(movement) = movements
+        .iter()
+        .find(|movement| matches!(movement.direction, Direction::Left | Direction::Right))
+    {
+        return Err(Box::new(Day2Error(format!(
+            "Lateral movement is only supported in 3D: {}",
+            movement
+        ))));
+    }
+
+    // Without any lateral movement, the 3D model stays in the plane
+    let position = follow_route_3d(movements, model)?;
+
+    Ok(Position {
+        horizontal: position.horizontal,
+        depth: position.depth,
+    })
+}
+
+/// Follow a list of already parsed movements from the origin under the given model, allowing
+/// lateral movement as well. Routes that go further than an `i32` can hold are an error.
+fn follow_route_3d(movements: &[Movement], model: Model) -> Result<Position3d> {
+    let mut position = Position3d {
+        horizontal: 0,
+        lateral: 0,
+        depth: 0,
+    };
+    let mut pitch = 0;
+    let mut yaw = 0;
+
+    for movement in movements {
+        let distance = movement.distance;
+        let moved = match (model, movement.direction) {
+            (Model::Basic, Direction::Forward) => add(&mut position.horizontal, Some(distance)),
+            (Model::Basic, Direction::Down) => add(&mut position.depth, Some(distance)),
+            (Model::Basic, Direction::Up) => add(&mut position.depth, distance.checked_neg()),
+            (Model::Basic, Direction::Right) => add(&mut position.lateral, Some(distance)),
+            (Model::Basic, Direction::Left) => add(&mut position.lateral, distance.checked_neg()),
+            (Model::Aim, Direction::Forward) => {
+                add(&mut position.horizontal, Some(distance))
+                    && add(&mut position.depth, distance.checked_mul(pitch))
+                    && add(&mut position.lateral, distance.checked_mul(yaw))
+            }
+            (Model::Aim, Direction::Down) => add(&mut pitch, Some(distance)),
+            (Model::Aim, Direction::Up) => add(&mut pitch, distance.checked_neg()),
+            (Model::Aim, Direction::Right) => add(&mut yaw, Some(distance)),
+            (Model::Aim, Direction::Left) => add(&mut yaw, distance.checked_neg()),
+        };
+        if !moved {
+            return Err(Box::new(Day2Error(format!(
+                "Route goes out of range at: {}",
+                movement
+            ))));
+        }
+    }
+
+    Ok(position)
+}
+
+/// Add to `total` unless either the change or the new total overflowed
+fn add(total: &mut i32, change: Option<i32>) -> bool {
+    match change.and_then(|change| total.checked_add(change)) {
+        Some(sum) => {
+            *total = sum;
+            true
+        }
+        None => false,
+    }
+}
+
+/// The puzzle answer for a position, the horizontal position times the depth
+fn answer(position: &Position) -> Result<i32> {
+    position
+        .horizontal
+        .checked_mul(position.depth)
+        .ok_or_else(|| {
+            Box::new(Day2Error(format!("Answer for {:?} overflows", position))).into()
+        })
+}
+
+fn parse_movement(line: &str) -> Result<Movement> {
+    let mut split = line.split(' ');
+    let direction = match split.next() {
+        None => return Err(Box::new(Day2Error("No direction element in line".into()))),
+        Some(val) => match val {
+            "forward" => Direction::Forward,
+            "down" => Direction::Down,
+            "up" => Direction::Up,
+            "left" => Direction::Left,
+            "right" => Direction::Right,
+            "" => {
+                return Ok(Movement {
+                    direction: Direction::Forward,
+                    distance: 0,
+                })
+            }
+            other => return Err(Box::new(Day2Error(format!("Unknown direction: {}", other)))),
+        },
+    };
+
+    let distance = match split.next() {
+        None => return Err(Box::new(Day2Error("No distance element in line".into()))),
+        Some(val) => val.parse::<i32>()?,
+    };
+
+    Ok(Movement {
+        direction,
+        distance,
+    })
+}
+
+/// Parse a whole command file with the given mode, skipping lines that hold no command.
+///
+/// Errors are reported with the (1-based) line number they were found on. The newline at the end
+/// of the file is not treated as an empty line, even in strict mode.
+fn parse_commands<'a>(
+    lines: impl Iterator<Item = &'a str>,
+    mode: ParseMode,
+) -> Result<Vec<Movement>> {
+    let mut movements = vec![];
+    let mut lines = lines.enumerate().peekable();
+
+    while let Some((idx, line)) = lines.next() {
+        if line.is_empty() && lines.peek().is_none() {
+            break;
+        }
+        match parse_movement_with_mode(line, mode) {
+            Ok(Some(movement)) => movements.push(movement),
+            Ok(None) => continue,
+            Err(e) => return Err(Box::new(Day2Error(format!("line {}: {}", idx + 1, e.0)))),
+        }
+    }
+
+    Ok(movements)
+}
+
+/// Parse a single command with the given mode, see `ParseMode` for what each mode accepts.
+///
+/// Returns `None` for lines that are allowed but hold no command, such as blank lines and comments
+/// in lenient mode.
+fn parse_movement_with_mode(
+    line: &str,
+    mode: ParseMode,
+) -> std::result::Result<Option<Movement>, Day2Error> {
+    match mode {
+        ParseMode::Strict => parse_movement_strict(line).map(Some),
+        ParseMode::Lenient => parse_movement_lenient(line),
+    }
+}
+
+fn parse_movement_strict(line: &str) -> std::result::Result<Movement, Day2Error> {
+    if line.is_empty() {
+        return Err(Day2Error("Empty line".into()));
+    }
+    if let Some((column, char)) = line
+        .chars()
+        .enumerate()
+        .find(|(_, char)| char.is_whitespace() && *char != ' ')
+    {
+        return Err(Day2Error(format!(
+            "Unexpected whitespace {:?} at column {}",
+            char,
+            column + 1
+        )));
+    }
+    if line.ends_with(' ') {
+        return Err(Day2Error(format!(
+            "Trailing space at column {}",
+            line.chars().count()
+        )));
+    }
+
+    let mut split = line.split(' ');
+    let direction = match split.next() {
+        Some("forward") => Direction::Forward,
+        Some("down") => Direction::Down,
+        Some("up") => Direction::Up,
+        Some("left") => Direction::Left,
+        Some("right") => Direction::Right,
+        Some("") => return Err(Day2Error("Line starts with a space".into())),
+        Some(other) if parse_direction(other).is_some() => {
+            return Err(Day2Error(format!("Direction must be lowercase: {}", other)))
+        }
+        Some(other) => return Err(Day2Error(format!("Unknown direction: {}", other))),
+        None => return Err(Day2Error("No direction element in line".into())),
+    };
+
+    let distance = match split.next() {
+        None => return Err(Day2Error("No distance element in line".into())),
+        Some("") => {
+            return Err(Day2Error(
+                "Expected a single space between direction and distance".into(),
+            ))
+        }
+        Some(val) if val.starts_with('-') => {
+            return Err(Day2Error(format!("Negative distance: {}", val)))
+        }
+        Some(val) if !val.chars().all(|char| char.is_ascii_digit()) => {
+            return Err(Day2Error(format!("Invalid distance: {}", val)))
+        }
+        Some(val) => val
+            .parse::<i32>()
+            .map_err(|e| Day2Error(format!("Invalid distance {}: {}", val, e)))?,
+    };
+
+    let trailing = split.collect::<Vec<&str>>().join(" ");
+    if !trailing.is_empty() {
+        return Err(Day2Error(format!(
+            "Unexpected input after distance: {:?}",
+            trailing
+        )));
+    }
+
+    Ok(Movement {
+        direction,
+        distance,
+    })
+}
+
+fn parse_movement_lenient(line: &str) -> std::result::Result<Option<Movement>, Day2Error> {
+    // Everything after a `#` is a comment
+    let line = match line.split_once('#') {
+        Some((command, _comment)) => command,
+        None => line,
+    };
+
+    let mut split = line.split_whitespace();
+    let direction = match split.next() {
+        None => return Ok(None),
+        Some(val) => match parse_direction(val) {
+            Some(direction) => direction,
+            None => return Err(Day2Error(format!("Unknown direction: {}", val))),
+        },
+    };
+
+    let distance = match split.next() {
+        None => return Err(Day2Error("No distance element in line".into())),
+        Some(val) => val
+            .parse::<i32>()
+            .map_err(|e| Day2Error(format!("Invalid distance {}: {}", val, e)))?,
+    };
+
+    Ok(Some(Movement {
+        direction,
+        distance,
+    }))
+}
+
+/// Case-insensitive lookup of a direction
+fn parse_direction(val: &str) -> Option<Direction> {
+    match val.to_lowercase().as_str() {
+        "forward" => Some(Direction::Forward),
+        "down" => Some(Direction::Down),
+        "up" => Some(Direction::Up),
+        "left" => Some(Direction::Left),
+        "right" => Some(Direction::Right),
+        _ => None,
+    }
+}
+
+/// Produce a shorter list of movements that ends up in the same position as `lines` does under
+/// the given model.
+///
+/// No-ops (zero distances, including the empty line) are dropped and neighbouring commands that
+/// can be combined are merged. The result is rendered back to text and run through the same
+/// parser as the original, and an error is returned if the two don't end up in the same place.
+fn optimise_route<'a>(
+    lines: impl Iterator<Item = &'a str> + Clone,
+    model: Model,
+) -> Result<Vec<Movement>> {
+    let movements = lines
+        .clone()
+        .map(parse_movement)
+        .collect::<Result<Vec<Movement>>>()?;
+
+    // Following the original route first also rejects anything that isn't a 2D route
+    let expected = match model {
+        Model::Basic => parse_movements(lines)?,
+        Model::Aim => parse_movements_with_aim(lines)?,
+    };
+
+    let route = match model {
+        Model::Basic => optimise_basic(&movements),
+        Model::Aim => optimise_aim(&movements),
+    };
+
+    // Prove that the new route is equivalent by following both of them
+    let rendered = route
+        .iter()
+        .map(|movement| movement.to_string())
+        .collect::<Vec<String>>();
+    let rendered_lines = rendered.iter().map(String::as_str);
+
+    let actual = match model {
+        Model::Basic => parse_movements(rendered_lines)?,
+        Model::Aim => parse_movements_with_aim(rendered_lines)?,
+    };
+
+    if expected != actual {
+        return Err(Box::new(Day2Error(format!(
+            "Optimised route ends at {:?} rather than {:?}",
+            actual, expected
+        ))));
+    }
+
+    Ok(route)
+}
+
+/// Without aim the order of the commands doesn't matter, so everything collapses into at most one
+/// `forward` and one `down`/`up`
+fn optimise_basic(movements: &[Movement]) -> Vec<Movement> {
+    let mut horizontal = 0;
+    let mut depth = 0;
+
+    for movement in movements {
+        match movement.direction {
+            Direction::Forward => horizontal += movement.distance,
+            Direction::Down => depth += movement.distance,
+            Direction::Up => depth -= movement.distance,
+            Direction::Left | Direction::Right => {
+                unreachable!("Lateral movements are rejected before optimising")
+            }
+        }
+    }
+
+    let mut route = vec![];
+    if horizontal != 0 {
+        route.push(Movement {
+            direction: Direction::Forward,
+            distance: horizontal,
+        });
+    }
+    if let Some(movement) = vertical_movement(depth) {
+        route.push(movement);
+    }
+
+    route
+}
+
+/// With aim the order matters, but runs of `down`/`up` can be merged into a single aim change and
+/// runs of `forward` can be merged as they all move along the same aim. Any aim changes after the
+/// last `forward` don't affect the position, so they are dropped. Movements whose sum doesn't fit
+/// in an `i32` are left unmerged, as a valid route can still have runs like that.
+fn optimise_aim(movements: &[Movement]) -> Vec<Movement> {
+    let mut route: Vec<Movement> = vec![];
+
+    for movement in movements {
+        if movement.distance == 0 {
+            continue;
+        }
+
+        let last = match route.last_mut() {
+            Some(last) => last,
+            None => {
+                route.push(*movement);
+                continue;
+            }
+        };
+
+        match (last.direction, movement.direction) {
+            (Direction::Forward, Direction::Forward) => {
+                match last.distance.checked_add(movement.distance) {
+                    Some(distance) => last.distance = distance,
+                    None => route.push(*movement),
+                }
+            }
+            (Direction::Down | Direction::Up, Direction::Down | Direction::Up) => {
+                // Both are aim changes, merge them into the net change. If they cancel out, the
+                // previous forward ends up on top again and the next forward merges into it.
+                match signed_distance(last).checked_add(signed_distance(movement)) {
+                    Some(net) => {
+                        route.pop();
+                        if let Some(merged) = vertical_movement(net) {
+                            route.push(merged);
+                        }
+                    }
+                    None => route.push(*movement),
+                }
+            }
+            (_, _) => route.push(*movement),
+        }
+    }
+
+    // Aim changes at the end of the route don't move the submarine
+    while matches!(route.last(), Some(movement) if movement.direction != Direction::Forward) {
+        route.pop();
+    }
+
+    route
+}
+
+/// The distance of a vertical movement, positive for `down` and negative for `up`
+fn signed_distance(movement: &Movement) -> i32 {
+    match movement.direction {
+        Direction::Up => -movement.distance,
+        _ => movement.distance,
+    }
+}
+
+/// Turn a net vertical change into a single movement, or `None` if it cancels out
+fn vertical_movement(net: i32) -> Option<Movement> {
+    match net.checked_neg() {
+        _ if net == 0 => None,
+        Some(distance) if net < 0 => Some(Movement {
+            direction: Direction::Up,
+            distance,
+        }),
+        // Going down by `i32::MIN` is the only way to write that much of an up
+        _ => Some(Movement {
+            direction: Direction::Down,
+            distance: net,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const EXAMPLE: &str = include_str!("example.txt");
+
+    #[test]
+    fn test_parse_movement() {
+        assert_eq!(
+            parse_movement("forward 5").unwrap(),
+            Movement {
+                direction: Direction::Forward,
+                distance: 5,
+            }
+        );
+
+        assert_eq!(
+            parse_movement("up 3").unwrap(),
+            Movement {
+                direction: Direction::Up,
+                distance: 3,
+            }
+        );
+
+        assert_eq!(
+            parse_movement("down 500").unwrap(),
+            Movement {
+                direction: Direction::Down,
+                distance: 500,
+            }
+        );
+
+        assert_eq!(
+            parse_movement("").unwrap(),
+            Movement {
+                direction: Direction::Forward,
+                distance: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_movement_invalid() {
+        assert!(parse_movement("forward forward").is_err());
+        assert!(parse_movement("forward").is_err());
+        assert!(parse_movement("sideways 4").is_err());
+    }
+
+    #[test]
+    fn test_parse_movements() {
+        let lines = vec![
+            "forward 5",
+            "down 5",
+            "forward 8",
+            "up 3",
+            "down 8",
+            "forward 2",
+        ];
+
+        let position = parse_movements(lines.into_iter()).unwrap();
+
+        assert_eq!(
+            position,
+            Position {
+                horizontal: 15,
+                depth: 10,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_movements_with_aim() {
+        let lines = vec![
+            "forward 5",
+            "down 5",
+            "forward 8",
+            "up 3",
+            "down 8",
+            "forward 2",
+        ];
+
+        let position = parse_movements_with_aim(lines.into_iter()).unwrap();
+
+        assert_eq!(
+            position,
+            Position {
+                horizontal: 15,
+                depth: 60,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_movements_rejects_lateral() {
+        let lines = vec!["forward 5", "left 3"];
+
+        assert!(parse_movements(lines.clone().into_iter()).is_err());
+        assert!(parse_movements_with_aim(lines.into_iter()).is_err());
+    }
+
+    #[test]
+    fn test_follow_route_3d_without_lateral_matches_2d() {
+        let movements = vec![
+            "forward 5",
+            "down 5",
+            "forward 8",
+            "up 3",
+            "down 8",
+            "forward 2",
+        ]
+        .into_iter()
+        .map(|line| parse_movement(line).unwrap())
+        .collect::<Vec<Movement>>();
+
+        assert_eq!(
+            follow_route_3d(&movements, Model::Basic).unwrap(),
+            Position3d {
+                horizontal: 15,
+                lateral: 0,
+                depth: 10,
+            }
+        );
+        assert_eq!(
+            follow_route_3d(&movements, Model::Aim).unwrap(),
+            Position3d {
+                horizontal: 15,
+                lateral: 0,
+                depth: 60,
+            }
+        );
+    }
+
+    #[test]
+    fn test_follow_route_3d() {
+        let movements = vec!["forward 5", "right 2", "down 1", "forward 3", "left 5"]
+            .into_iter()
+            .map(|line| parse_movement(line).unwrap())
+            .collect::<Vec<Movement>>();
+
+        assert_eq!(
+            follow_route_3d(&movements, Model::Basic).unwrap(),
+            Position3d {
+                horizontal: 8,
+                lateral: -3,
+                depth: 1,
+            }
+        );
+        assert_eq!(
+            follow_route_3d(&movements, Model::Aim).unwrap(),
+            Position3d {
+                horizontal: 8,
+                lateral: 6,
+                depth: 3,
+            }
+        );
+    }
+
+    #[test]
+    fn test_route_out_of_range() {
+        assert!(part1("forward 2147483647\nforward 1").is_err());
+        assert!(part1("up -2147483648").is_err());
+        assert!(part2("down 65536\nforward 65536").is_err());
+        // The position fits, but the answer doesn't
+        assert!(part1("forward 65536\ndown 65536").is_err());
+        assert_eq!(part1("forward 2147483647\ndown 1").unwrap(), 2147483647);
+    }
+
+    #[test]
+    fn test_position_3d_distances() {
+        let position = Position3d {
+            horizontal: 2,
+            lateral: -3,
+            depth: 6,
+        };
+
+        assert_eq!(position.manhattan_distance(), 11);
+        assert_eq!(position.euclidean_distance(), 7.0);
+    }
+
+    #[test]
+    fn test_parse_movement_strict() {
+        assert_eq!(
+            parse_movement_with_mode("forward 5", ParseMode::Strict).unwrap(),
+            Some(Movement {
+                direction: Direction::Forward,
+                distance: 5,
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_movement_strict_invalid() {
+        let cases = vec![
+            ("", "Empty line"),
+            (
+                "forward 5 extra",
+                "Unexpected input after distance: \"extra\"",
+            ),
+            ("forward -5", "Negative distance: -5"),
+            ("Forward 5", "Direction must be lowercase: Forward"),
+            ("forward\t5", "Unexpected whitespace '\\t' at column 8"),
+            (
+                "forward  5",
+                "Expected a single space between direction and distance",
+            ),
+            ("forward", "No distance element in line"),
+            ("forward +5", "Invalid distance: +5"),
+            ("forward 5 ", "Trailing space at column 10"),
+            ("forward 5  ", "Trailing space at column 11"),
+        ];
+
+        for (line, message) in cases {
+            assert_eq!(
+                parse_movement_with_mode(line, ParseMode::Strict),
+                Err(Day2Error(message.into())),
+                "{:?}",
+                line
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_movement_lenient() {
+        assert_eq!(
+            parse_movement_with_mode("  FORWARD\t 5   # full speed", ParseMode::Lenient).unwrap(),
+            Some(Movement {
+                direction: Direction::Forward,
+                distance: 5,
+            })
+        );
+        assert_eq!(
+            parse_movement_with_mode("Up 3 extra", ParseMode::Lenient).unwrap(),
+            Some(Movement {
+                direction: Direction::Up,
+                distance: 3,
+            })
+        );
+        assert_eq!(
+            parse_movement_with_mode("# just a comment", ParseMode::Lenient).unwrap(),
+            None
+        );
+        assert_eq!(
+            parse_movement_with_mode("", ParseMode::Lenient).unwrap(),
+            None
+        );
+        assert!(parse_movement_with_mode("sideways 4", ParseMode::Lenient).is_err());
+    }
+
+    #[test]
+    fn test_solver_parse_matches_parts() {
+        for input in ["forward 5\ndown 3\n", "forward 5\n\ndown 3", "forward 5\nsideways 3\n"] {
+            assert_eq!(
+                Day.parse(input).is_ok(),
+                part1(input).is_ok(),
+                "{:?}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_commands_strict() {
+        let lines = vec!["forward 5", "down 5", ""];
+
+        assert_eq!(
+            parse_commands(lines.into_iter(), ParseMode::Strict)
+                .unwrap()
+                .len(),
+            2
+        );
+
+        let lines = vec!["forward 5", "", "down 5", ""];
+        let err = parse_commands(lines.into_iter(), ParseMode::Strict).unwrap_err();
+
+        assert_eq!(err.to_string(), "Invalid input: line 2: Empty line");
+    }
+
+    #[test]
+    fn test_optimise_route_basic() {
+        let lines = vec![
+            "forward 5",
+            "down 5",
+            "forward 8",
+            "up 3",
+            "down 8",
+            "forward 2",
+        ];
+
+        let route = optimise_route(lines.into_iter(), Model::Basic).unwrap();
+
+        assert_eq!(
+            route,
+            vec![
+                Movement {
+                    direction: Direction::Forward,
+                    distance: 15,
+                },
+                Movement {
+                    direction: Direction::Down,
+                    distance: 10,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_optimise_route_aim() {
+        let lines = vec![
+            "forward 5",
+            "down 5",
+            "forward 8",
+            "up 3",
+            "down 8",
+            "forward 2",
+        ];
+
+        let route = optimise_route(lines.into_iter(), Model::Aim).unwrap();
+
+        assert_eq!(
+            route.iter().map(|m| m.to_string()).collect::<Vec<String>>(),
+            vec!["forward 5", "down 5", "forward 8", "down 5", "forward 2"]
+        );
+    }
+
+    #[test]
+    fn test_optimise_route_aim_drops_no_ops() {
+        let lines = vec![
+            "forward 5",
+            "",
+            "down 2",
+            "up 2",
+            "forward 0",
+            "forward 3",
+            "up 4",
+            "",
+        ];
+
+        let route = optimise_route(lines.into_iter(), Model::Aim).unwrap();
+
+        assert_eq!(
+            route,
+            vec![Movement {
+                direction: Direction::Forward,
+                distance: 8,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_optimise_route_aim_overflow() {
+        let lines = vec![
+            "down 2",
+            "forward -1073741824",
+            "up 1",
+            "forward 2147483647",
+            "forward 1",
+        ];
+        assert_eq!(part2(&lines.join("\n")).unwrap(), 0);
+
+        let route = optimise_route(lines.clone().into_iter(), Model::Aim).unwrap();
+        assert_eq!(route.len(), lines.len());
+
+        let lines = vec!["up 2147483647", "up 1", "forward 1"];
+        let route = optimise_route(lines.into_iter(), Model::Aim).unwrap();
+        assert_eq!(
+            route,
+            vec![
+                Movement {
+                    direction: Direction::Down,
+                    distance: i32::MIN,
+                },
+                Movement {
+                    direction: Direction::Forward,
+                    distance: 1,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_optimise_route_invalid() {
+        let lines = vec!["forward 5", "sideways 4"];
+
+        assert!(optimise_route(lines.into_iter(), Model::Aim).is_err());
+    }
+
+    #[test]
+    fn test_part1_example() {
+        assert_eq!(part1(EXAMPLE).unwrap(), 150);
+    }
+
+    #[test]
+    fn test_part2_example() {
+        assert_eq!(part2(EXAMPLE).unwrap(), 900);
+    }
+
+    #[test]
+    #[cfg_attr(not(has_answer = "2021-2-1"), ignore = "no input.txt with an answer")]
+    fn test_part1() {
+        let (input, expected) = crate::answers::real_input(2021, 2, 1);
+        assert_eq!(part1(&input).unwrap().to_string(), expected);
+    }
+
+    #[test]
+    #[cfg_attr(not(has_answer = "2021-2-2"), ignore = "no input.txt with an answer")]
+    fn test_part2() {
+        let (input, expected) = crate::answers::real_input(2021, 2, 2);
+        assert_eq!(part2(&input).unwrap().to_string(), expected);
+    }
+
+    fn movement() -> impl Strategy<Value = Movement> {
+        let direction = prop_oneof![
+            Just(Direction::Forward),
+            Just(Direction::Down),
+            Just(Direction::Up),
+        ];
+        (direction, 0..100).prop_map(|(direction, distance)| Movement {
+            distance,
+            direction,
+        })
+    }
+
+    fn lines(movements: &[Movement]) -> Vec<String> {
+        movements.iter().map(Movement::to_string).collect()
+    }
+
+    proptest! {
+        #[test]
+        fn prop_parse_movement_round_trips(movement in movement()) {
+            prop_assert_eq!(parse_movement(&movement.to_string()).unwrap(), movement);
+        }
+
+        #[test]
+        fn prop_parse_movements_ignores_order(
+            (movements, shuffled) in prop::collection::vec(movement(), 0..30)
+                .prop_flat_map(|movements| (Just(movements.clone()), Just(movements).prop_shuffle()))
+        ) {
+            let position = parse_movements(lines(&movements).iter().map(String::as_str)).unwrap();
+            let shuffled = parse_movements(lines(&shuffled).iter().map(String::as_str)).unwrap();
+
+            prop_assert_eq!(position, shuffled);
+        }
+
+        #[test]
+        fn prop_aim_ignores_order_of_vertical_moves_between_forwards(
+            movements in prop::collection::vec(movement(), 0..30)
+        ) {
+            // Sort each run of up and down moves, which only changes the aim in between forwards
+            let mut sorted = movements.clone();
+            for run in sorted.split_mut(|movement| movement.direction == Direction::Forward) {
+                run.sort_by_key(signed_distance);
+            }
+
+            let position = parse_movements_with_aim(lines(&movements).iter().map(String::as_str));
+            let sorted = parse_movements_with_aim(lines(&sorted).iter().map(String::as_str));
+
+            prop_assert_eq!(position.unwrap(), sorted.unwrap());
+        }
+    }
+}
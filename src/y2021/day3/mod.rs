@@ -0,0 +1,1756 @@
+use std::cmp::Ordering;
+use std::error;
+use std::fmt;
+use std::thread;
+use std::time::Instant;
+
+use crate::report::json_string;
+use crate::{Answer, Command, Solver};
+
+type Result<T> = std::result::Result<T, Box<dyn error::Error>>;
+
+#[derive(Debug, PartialEq)]
+struct Day3Error(String);
+
+impl fmt::Display for Day3Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid input: {}", self.0)
+    }
+}
+impl error::Error for Day3Error {}
+
+pub struct Day;
+
+impl Solver for Day {
+    fn parse(&self, input: &str) -> Result<()> {
+        DiagnosticReport::parse(input.split('\n'))?;
+        Ok(())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer> {
+        part1(input)
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer> {
+        part2(input)
+    }
+
+    fn commands(&self) -> &'static [Command] {
+        &[
+            Command {
+                name: "tie-break",
+                usage: "<one|zero|error>",
+                run: solve_with_tie_break,
+            },
+            Command {
+                name: "trace",
+                usage: "<oxygen|co2>",
+                run: trace,
+            },
+            Command {
+                name: "filter",
+                usage: "<majority|minority|0|1> [ltr|rtl|<positions>]",
+                run: filter,
+            },
+            Command {
+                name: "summary",
+                usage: "[json]",
+                run: summary,
+            },
+            Command {
+                name: "bits",
+                usage: "<position>",
+                run: bits,
+            },
+            Command {
+                name: "bench",
+                usage: "[rows] [threads]",
+                run: bench,
+            },
+        ]
+    }
+}
+
+/// Solve both parts with ties broken the given way
+fn solve_with_tie_break(args: &[String], input: &str) -> Result<()> {
+    let tie_break = match args.first().map(String::as_str) {
+        Some("one") => TieBreak::PreferOne,
+        Some("zero") => TieBreak::PreferZero,
+        Some("error") => TieBreak::Error,
+        _ => {
+            return Err(Box::new(Day3Error(
+                "Expected a tie break, one, zero or error".into(),
+            )))
+        }
+    };
+    println!(
+        "Day 3 - Part 1: {}",
+        part1_with_tie_break(input, tie_break)?
+    );
+    println!(
+        "Day 3 - Part 2: {}",
+        part2_with_tie_break(input, tie_break)?
+    );
+
+    Ok(())
+}
+
+/// Print every pass of the filter for one of the ratings
+fn trace(args: &[String], input: &str) -> Result<()> {
+    let keep_most_common = match args.first().map(String::as_str) {
+        Some("oxygen") => true,
+        Some("co2") => false,
+        _ => {
+            return Err(Box::new(Day3Error(
+                "Expected a rating, oxygen or co2".into(),
+            )))
+        }
+    };
+    let report = DiagnosticReport::parse(input.split('\n'))?;
+
+    let (rating, trace) = report.rating_with_trace(keep_most_common, TieBreak::RATINGS);
+    print!("{}", trace);
+    println!("Rating: {}", rating?);
+
+    Ok(())
+}
+
+/// Run a custom filter over the report and print the row that's left. Positions are comma
+/// separated and counted from the left, and the order defaults to left to right.
+fn filter(args: &[String], input: &str) -> Result<()> {
+    let criterion: Box<dyn Criterion> = match args.first().map(String::as_str) {
+        Some("majority") => Box::new(Majority(TieBreak::RATINGS)),
+        Some("minority") => Box::new(Minority(TieBreak::RATINGS)),
+        Some("0") => Box::new(Fixed(0)),
+        Some("1") => Box::new(Fixed(1)),
+        _ => {
+            return Err(Box::new(Day3Error(
+                "Expected a criterion, majority, minority, 0 or 1".into(),
+            )))
+        }
+    };
+    let order = match args.get(1).map(String::as_str) {
+        None | Some("ltr") => PositionOrder::LeftToRight,
+        Some("rtl") => PositionOrder::RightToLeft,
+        Some(positions) => PositionOrder::Custom(
+            positions
+                .split(',')
+                .map(|idx| idx.parse::<usize>())
+                .collect::<std::result::Result<Vec<usize>, _>>()?,
+        ),
+    };
+    let report = DiagnosticReport::parse(input.split('\n'))?;
+
+    let filter = BitFilter { criterion, order };
+    let row = filter.run(&report)?;
+    println!("{:0width$b} ({})", row, row, width = report.width);
+
+    Ok(())
+}
+
+/// Print everything that can be worked out from the report, as text or JSON
+fn summary(args: &[String], input: &str) -> Result<()> {
+    let summary = diagnostic_summary(input)?;
+
+    match args.first().map(String::as_str) {
+        None => print!("{}", summary),
+        Some("json") => println!("{}", summary.to_json()),
+        Some(format) => {
+            return Err(Box::new(Day3Error(format!(
+                "Unknown summary format {}, expected json",
+                format
+            ))))
+        }
+    }
+
+    Ok(())
+}
+
+/// Everything that can be worked out from a report, see `DiagnosticSummary`. Only a report that
+/// doesn't parse is an error, a rating that can't be found is recorded in the summary instead.
+pub fn diagnostic_summary(input: &str) -> Result<DiagnosticSummary> {
+    DiagnosticReport::parse(input.split('\n'))?.summary()
+}
+
+/// Print how the ones and zeroes are spread at one bit position, counted from the left
+fn bits(args: &[String], input: &str) -> Result<()> {
+    let idx = match args.first() {
+        Some(idx) => idx.parse::<usize>()?,
+        None => return Err(Box::new(Day3Error("Expected a bit position".into()))),
+    };
+    let report = DiagnosticReport::parse(input.split('\n'))?;
+    if idx >= report.width {
+        return Err(Box::new(Day3Error(format!(
+            "Bit {} is outside of the {} bit report",
+            idx, report.width
+        ))));
+    }
+
+    let total = report.rows.len();
+    let ones = count_ones_at(&report.rows, report.shift(idx));
+    let most_common = match (ones * 2).cmp(&total) {
+        Ordering::Greater => "1",
+        Ordering::Less => "0",
+        Ordering::Equal => "tied",
+    };
+    println!(
+        "Bit {}: {} ones, {} zeroes, most common {}, entropy {:.4}",
+        idx,
+        ones,
+        total - ones,
+        most_common,
+        bit_entropy(ones, total)
+    );
+
+    Ok(())
+}
+
+/// Compare the ways of counting bits on generated reports, rather than the puzzle input. Rows is a
+/// comma separated list of report sizes, defaulting to 1000000, and threads defaults to the
+/// available parallelism.
+fn bench(args: &[String], _input: &str) -> Result<()> {
+    let sizes = match args.first() {
+        Some(sizes) => sizes
+            .split(',')
+            .map(|size| size.parse::<usize>())
+            .collect::<std::result::Result<Vec<usize>, _>>()?,
+        None => vec![1_000_000],
+    };
+    let threads = match args.get(1) {
+        Some(threads) => threads.parse::<usize>()?,
+        None => thread::available_parallelism()?.get(),
+    };
+
+    for rows in sizes {
+        let contents = generate_report(rows, 12);
+        let lines = contents.split('\n').collect::<Vec<&str>>();
+        println!("{} rows, {} threads", rows, threads);
+
+        let start = Instant::now();
+        let expected = get_bit_counts(lines.iter().copied())?;
+        println!("    get_bit_counts:            {:?}", start.elapsed());
+
+        let start = Instant::now();
+        let counts = get_bit_counts_parallel(&lines, threads)?;
+        println!("    get_bit_counts_parallel:   {:?}", start.elapsed());
+        check_counts(&expected, &counts)?;
+
+        let start = Instant::now();
+        let report = DiagnosticReport::parse(lines.iter().copied())?;
+        println!("    DiagnosticReport::parse:   {:?}", start.elapsed());
+
+        let start = Instant::now();
+        let counts = report.bit_counts();
+        println!("    bit_counts (packed):       {:?}", start.elapsed());
+        check_counts(&expected, &counts)?;
+
+        let start = Instant::now();
+        let counts = report.bit_counts_parallel(threads)?;
+        println!("    bit_counts_parallel:       {:?}", start.elapsed());
+        check_counts(&expected, &counts)?;
+    }
+
+    Ok(())
+}
+
+fn check_counts(expected: &[i32], actual: &[i32]) -> Result<()> {
+    if expected != actual {
+        return Err(Box::new(Day3Error(format!(
+            "Counts differ: expected {:?}, got {:?}",
+            expected, actual
+        ))));
+    }
+    Ok(())
+}
+
+/// Generate a report of random rows, from a fixed seed so runs are comparable
+fn generate_report(rows: usize, width: usize) -> String {
+    let mut state: u64 = 0x2021_0003;
+    let mut contents = String::with_capacity(rows * (width + 1));
+
+    for _ in 0..rows {
+        for bit in 0..width {
+            if bit % 64 == 0 {
+                // xorshift64
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+            }
+            contents.push(if (state >> (bit % 64)) & 1 == 1 {
+                '1'
+            } else {
+                '0'
+            });
+        }
+        contents.push('\n');
+    }
+
+    contents
+}
+
+fn part1(input: &str) -> Result<Answer> {
+    part1_with_tie_break(input, TieBreak::GAMMA_EPSILON)
+}
+
+fn part2(input: &str) -> Result<Answer> {
+    part2_with_tie_break(input, TieBreak::RATINGS)
+}
+
+fn part1_with_tie_break(input: &str, tie_break: TieBreak) -> Result<Answer> {
+    let lines = input.split('\n');
+
+    let bit_counts = get_bit_counts(lines)?;
+    let (gamma, epsilon) = calculate_gamma_epsilon(bit_counts, tie_break)?;
+
+    Ok(multiply(gamma, epsilon))
+}
+
+fn part2_with_tie_break(input: &str, tie_break: TieBreak) -> Result<Answer> {
+    let report = DiagnosticReport::parse(input.split('\n'))?;
+
+    let oxygen_generator_rating = report.rating(true, tie_break)?;
+    let co2_scrubber_rating = report.rating(false, tie_break)?;
+
+    Ok(multiply(oxygen_generator_rating, co2_scrubber_rating))
+}
+
+/// Multiply two values for the final answer. The widest reports need up to 256 bits for it, so
+/// when it doesn't fit in a `u128` it's worked out on 64-bit limbs and written out in decimal.
+fn multiply(a: u128, b: u128) -> Answer {
+    if let Some(val) = a.checked_mul(b) {
+        return Answer::Unsigned(val);
+    }
+
+    // Long multiplication, least significant limb first
+    let limbs = |val: u128| [val as u64, (val >> 64) as u64];
+    let mut product = [0u64; 4];
+    for (i, x) in limbs(a).into_iter().enumerate() {
+        let mut carry = 0u128;
+        for (j, y) in limbs(b).into_iter().enumerate() {
+            let val = x as u128 * y as u128 + product[i + j] as u128 + carry;
+            product[i + j] = val as u64;
+            carry = val >> 64;
+        }
+        product[i + 2] = carry as u64;
+    }
+
+    // Long division by 10^19, the most decimal digits that fit in a limb, for the digits from the
+    // right
+    const CHUNK: u128 = 10_000_000_000_000_000_000;
+    let mut chunks = Vec::new();
+    while product.iter().any(|&limb| limb != 0) {
+        let mut rem = 0u128;
+        for limb in product.iter_mut().rev() {
+            let val = (rem << 64) | *limb as u128;
+            *limb = (val / CHUNK) as u64;
+            rem = val % CHUNK;
+        }
+        chunks.push(rem);
+    }
+
+    let mut chunks = chunks.into_iter().rev();
+    let mut digits = chunks.next().unwrap_or(0).to_string();
+    for chunk in chunks {
+        digits.push_str(&format!("{:019}", chunk));
+    }
+    Answer::Text(digits)
+}
+
+/// Which bit counts as the most common when a position has as many ones as zeroes. The least
+/// common bit is always the other one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TieBreak {
+    /// 1 is the most common, so the oxygen generator rating keeps ones and the CO2 scrubber rating
+    /// keeps zeroes
+    PreferOne,
+    /// 0 is the most common
+    PreferZero,
+    /// A tie is an error
+    Error,
+}
+
+impl TieBreak {
+    /// The puzzle's rule for gamma and epsilon, where a tie doesn't set the bit in the gamma
+    const GAMMA_EPSILON: TieBreak = TieBreak::PreferZero;
+
+    /// The puzzle's rule for the ratings, where a tie counts as 1 being the most common
+    const RATINGS: TieBreak = TieBreak::PreferOne;
+
+    /// The most common bit at the position `idx` (from the left), given the number of ones minus
+    /// the number of zeroes there
+    fn most_common(self, balance: i32, idx: usize) -> Result<u128> {
+        match (balance, self) {
+            (balance, _) if balance > 0 => Ok(1),
+            (balance, _) if balance < 0 => Ok(0),
+            (_, TieBreak::PreferOne) => Ok(1),
+            (_, TieBreak::PreferZero) => Ok(0),
+            (_, TieBreak::Error) => Err(Box::new(Day3Error(format!(
+                "Ones and zeroes are equally common at bit {}",
+                idx
+            )))),
+        }
+    }
+}
+
+/// The widest lines a report can hold, as each line is packed into a `u128`
+const MAX_WIDTH: usize = 128;
+
+/// A diagnostic report with every line parsed once into the low `width` bits of a `u128`, with
+/// the first character of the line as the most significant bit
+#[derive(Debug, PartialEq)]
+struct DiagnosticReport {
+    width: usize,
+    rows: Vec<u128>,
+}
+
+impl DiagnosticReport {
+    /// Parse the lines of a report, which are checked with `validate_report` first
+    fn parse<'a>(lines: impl Iterator<Item = &'a str>) -> Result<Self> {
+        let lines = validate_report(lines)?;
+
+        let width = lines[0].len();
+        if width > MAX_WIDTH {
+            return Err(Box::new(Day3Error(format!(
+                "Lines of {} bits are wider than the supported {} bits",
+                width, MAX_WIDTH
+            ))));
+        }
+
+        let rows = lines
+            .into_iter()
+            .map(|line| {
+                line.bytes()
+                    .fold(0, |row, bit| (row << 1) | (bit == b'1') as u128)
+            })
+            .collect();
+
+        Ok(DiagnosticReport { width, rows })
+    }
+
+    /// The shift needed to get the bit at `idx`, counting from the left
+    fn shift(&self, idx: usize) -> usize {
+        self.width - 1 - idx
+    }
+
+    /// Same as `get_bit_counts`, ones minus zeroes for each bit from the left
+    fn bit_counts(&self) -> Vec<i32> {
+        let total = self.rows.len() as i32;
+
+        (0..self.width)
+            .map(|idx| {
+                let ones = count_ones_at(&self.rows, self.shift(idx)) as i32;
+                ones - (total - ones)
+            })
+            .collect()
+    }
+
+    /// Same as `bit_counts`, but with the rows split across `threads` threads. Each thread adds up
+    /// every bit of a row at once into a fixed size array, which the compiler can vectorise,
+    /// rather than going over the rows once per bit.
+    fn bit_counts_parallel(&self, threads: usize) -> Result<Vec<i32>> {
+        let chunk_size = chunk_size(self.rows.len(), threads);
+
+        let ones = thread::scope(|scope| {
+            let handles = self
+                .rows
+                .chunks(chunk_size)
+                .map(|chunk| scope.spawn(move || count_ones_per_bit(chunk, self.width)))
+                .collect::<Vec<_>>();
+
+            let mut ones = [0; MAX_WIDTH];
+            for handle in handles {
+                let chunk_ones = handle
+                    .join()
+                    .map_err(|_| Day3Error("Counting thread panicked".into()))?;
+                for (total, count) in ones.iter_mut().zip(chunk_ones) {
+                    *total += count;
+                }
+            }
+            Ok::<_, Day3Error>(ones)
+        })?;
+
+        let total = self.rows.len() as i64;
+        Ok((0..self.width)
+            .map(|idx| (2 * ones[self.shift(idx)] as i64 - total) as i32)
+            .collect())
+    }
+
+    /// Summarise the report, with ties broken the way the puzzle does for each value
+    fn summary(&self) -> Result<DiagnosticSummary> {
+        let bit_counts = self.bit_counts();
+        let total = self.rows.len();
+
+        let bits = bit_counts
+            .iter()
+            .map(|balance| {
+                let ones = (total as i32 + balance) as usize / 2;
+                BitSummary {
+                    ones,
+                    zeroes: total - ones,
+                    entropy: bit_entropy(ones, total),
+                }
+            })
+            .collect();
+
+        let (gamma, epsilon) = calculate_gamma_epsilon(bit_counts, TieBreak::GAMMA_EPSILON)?;
+        let rating = |keep_most_common| {
+            BitFilter::rating(keep_most_common, TieBreak::RATINGS)
+                .run_with_passes(self)
+                .map(|(value, passes)| RatingSummary { value, passes })
+                .map_err(|e| e.to_string())
+        };
+        let oxygen_generator_rating = rating(true);
+        let co2_scrubber_rating = rating(false);
+
+        Ok(DiagnosticSummary {
+            width: self.width,
+            rows: total,
+            bits,
+            gamma,
+            epsilon,
+            oxygen_generator_rating,
+            co2_scrubber_rating,
+        })
+    }
+
+    /// Filter the rows by the bit criteria from Day 3 Part 2, going from the leftmost bit to the
+    /// right until one row remains
+    fn rating(&self, keep_most_common: bool, tie_break: TieBreak) -> Result<u128> {
+        BitFilter::rating(keep_most_common, tie_break).run(self)
+    }
+
+    /// Same as `rating`, but also records every pass of the filter, see
+    /// `BitFilter::run_with_trace`
+    fn rating_with_trace(
+        &self,
+        keep_most_common: bool,
+        tie_break: TieBreak,
+    ) -> (Result<u128>, Trace) {
+        BitFilter::rating(keep_most_common, tie_break).run_with_trace(self)
+    }
+}
+
+/// Decides which bit rows need at a position to survive a pass of a `BitFilter`, given the
+/// position (from the left) and the number of ones and zeroes among the remaining rows there.
+///
+/// Closures with the same arguments returning the bit to keep can be used as a criterion directly.
+trait Criterion {
+    fn keep(&self, idx: usize, ones: usize, zeroes: usize) -> Result<u128>;
+}
+
+/// Keep the most common bit, as for the oxygen generator rating
+struct Majority(TieBreak);
+
+/// Keep the least common bit, as for the CO2 scrubber rating
+struct Minority(TieBreak);
+
+/// Always keep the same bit
+struct Fixed(u128);
+
+impl Criterion for Majority {
+    fn keep(&self, idx: usize, ones: usize, zeroes: usize) -> Result<u128> {
+        self.0.most_common(ones as i32 - zeroes as i32, idx)
+    }
+}
+
+impl Criterion for Minority {
+    fn keep(&self, idx: usize, ones: usize, zeroes: usize) -> Result<u128> {
+        Ok(self.0.most_common(ones as i32 - zeroes as i32, idx)? ^ 1)
+    }
+}
+
+impl Criterion for Fixed {
+    fn keep(&self, _idx: usize, _ones: usize, _zeroes: usize) -> Result<u128> {
+        Ok(self.0)
+    }
+}
+
+impl<F> Criterion for F
+where
+    F: Fn(usize, usize, usize) -> u128,
+{
+    fn keep(&self, idx: usize, ones: usize, zeroes: usize) -> Result<u128> {
+        Ok(self(idx, ones, zeroes))
+    }
+}
+
+/// The order a `BitFilter` goes through the bit positions in, each position is visited once
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PositionOrder {
+    LeftToRight,
+    RightToLeft,
+    /// Positions counting from the left, which don't have to cover every position
+    Custom(Vec<usize>),
+}
+
+/// Narrows the rows of a report down to one, by going through the bit positions in `order` and
+/// only keeping the rows that have the bit chosen by `criterion` at each position. Filtering stops
+/// as soon as one row is left.
+///
+/// The oxygen generator and CO2 scrubber ratings from Day 3 Part 2 are `Majority` and `Minority`
+/// from left to right.
+struct BitFilter {
+    criterion: Box<dyn Criterion>,
+    order: PositionOrder,
+}
+
+impl BitFilter {
+    fn new(criterion: impl Criterion + 'static, order: PositionOrder) -> Self {
+        BitFilter {
+            criterion: Box::new(criterion),
+            order,
+        }
+    }
+
+    /// The filter for the oxygen generator rating if `keep_most_common` is set, otherwise the CO2
+    /// scrubber rating
+    fn rating(keep_most_common: bool, tie_break: TieBreak) -> Self {
+        if keep_most_common {
+            BitFilter::new(Majority(tie_break), PositionOrder::LeftToRight)
+        } else {
+            BitFilter::new(Minority(tie_break), PositionOrder::LeftToRight)
+        }
+    }
+
+    /// Run the filter, returning the only remaining row, or an error if the rows run out or more
+    /// than one is left after the last position
+    fn run(&self, report: &DiagnosticReport) -> Result<u128> {
+        Ok(self.filter(report, None)?.0)
+    }
+
+    /// Same as `run`, but also returns how many passes it took to get down to one row
+    fn run_with_passes(&self, report: &DiagnosticReport) -> Result<(u128, usize)> {
+        self.filter(report, None)
+    }
+
+    /// Same as `run`, but also records every pass of the filter. The trace is returned even when
+    /// filtering fails, as that's usually when it's needed.
+    fn run_with_trace(&self, report: &DiagnosticReport) -> (Result<u128>, Trace) {
+        let mut trace = Trace {
+            width: report.width,
+            steps: vec![],
+        };
+        let result = self.filter(report, Some(&mut trace));
+
+        (result.map(|(row, _passes)| row), trace)
+    }
+
+    fn positions(&self, width: usize) -> Result<Vec<usize>> {
+        match &self.order {
+            PositionOrder::LeftToRight => Ok((0..width).collect()),
+            PositionOrder::RightToLeft => Ok((0..width).rev().collect()),
+            PositionOrder::Custom(positions) => {
+                if let Some(idx) = positions.iter().find(|idx| **idx >= width) {
+                    return Err(Box::new(Day3Error(format!(
+                        "Position {} is outside of {} bits",
+                        idx, width
+                    ))));
+                }
+                Ok(positions.clone())
+            }
+        }
+    }
+
+    fn filter(
+        &self,
+        report: &DiagnosticReport,
+        mut trace: Option<&mut Trace>,
+    ) -> Result<(u128, usize)> {
+        let mut items = report.rows.clone();
+        let mut passes = 0;
+
+        for idx in self.positions(report.width)? {
+            if items.len() <= 1 {
+                break;
+            }
+            passes += 1;
+            let shift = report.shift(idx);
+            let ones = count_ones_at(&items, shift);
+            let zeroes = items.len() - ones;
+
+            let keep = self.criterion.keep(idx, ones, zeroes)?;
+            if keep > 1 {
+                return Err(Box::new(Day3Error(format!(
+                    "Criterion chose {} at bit {}, which is not a bit",
+                    keep, idx
+                ))));
+            }
+
+            items.retain(|item| (item >> shift) & 1 == keep);
+
+            if let Some(trace) = trace.as_mut() {
+                trace.steps.push(FilterStep {
+                    idx,
+                    ones,
+                    zeroes,
+                    most_common: TieBreak::Error
+                        .most_common(ones as i32 - zeroes as i32, idx)
+                        .ok(),
+                    kept: keep,
+                    survivors: items.clone(),
+                });
+            }
+
+            if items.is_empty() {
+                return Err(Box::new(Day3Error(format!(
+                    "No rows left after keeping {} at bit {}",
+                    keep, idx
+                ))));
+            }
+        }
+
+        if items.len() == 1 {
+            return Ok((items[0], passes));
+        }
+
+        let count = items.len();
+        let rows = distinct_rows(items);
+        let listed = rows
+            .iter()
+            .map(|(row, count)| format!("{:0width$b} (x{})", row, count, width = report.width))
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        if rows.len() == 1 {
+            Err(Box::new(Day3Error(format!(
+                "{} identical rows can't be told apart: {}",
+                count, listed
+            ))))
+        } else {
+            Err(Box::new(Day3Error(format!(
+                "{} rows left after the last position: {}",
+                count, listed
+            ))))
+        }
+    }
+}
+
+/// The distinct rows in a list, in ascending order, with how often each one appears
+fn distinct_rows(mut rows: Vec<u128>) -> Vec<(u128, usize)> {
+    rows.sort_unstable();
+
+    let mut distinct: Vec<(u128, usize)> = vec![];
+    for row in rows {
+        match distinct.last_mut() {
+            Some((last, count)) if *last == row => *count += 1,
+            _ => distinct.push((row, 1)),
+        }
+    }
+
+    distinct
+}
+
+/// How the ones and zeroes are spread at one bit position of a report
+#[derive(Debug, PartialEq)]
+pub struct BitSummary {
+    pub ones: usize,
+    pub zeroes: usize,
+    /// Shannon entropy of the bit in bits, from 0 when every row agrees to 1 for an even split
+    pub entropy: f64,
+}
+
+/// The result of a rating filter, with the number of passes it took to get down to one row
+#[derive(Debug, PartialEq)]
+pub struct RatingSummary {
+    pub value: u128,
+    pub passes: usize,
+}
+
+/// Everything that can be worked out from a report, rather than just the two multiplied answers
+#[derive(Debug, PartialEq)]
+pub struct DiagnosticSummary {
+    pub width: usize,
+    pub rows: usize,
+    /// One per bit position, from the left
+    pub bits: Vec<BitSummary>,
+    pub gamma: u128,
+    pub epsilon: u128,
+    /// The rating, or why the filter couldn't get down to one row
+    pub oxygen_generator_rating: std::result::Result<RatingSummary, String>,
+    pub co2_scrubber_rating: std::result::Result<RatingSummary, String>,
+}
+
+impl DiagnosticSummary {
+    /// A value written out in binary, padded to the width of the report
+    pub fn binary(&self, val: u128) -> String {
+        format!("{:0width$b}", val, width = self.width)
+    }
+
+    /// The summary as a JSON object. Like the reports from `aoc --format json`, the values that
+    /// can take up to 128 bits are strings, as they don't fit in a JSON number.
+    pub fn to_json(&self) -> String {
+        let bits = self
+            .bits
+            .iter()
+            .map(|bit| {
+                format!(
+                    "{{\"ones\": {}, \"zeroes\": {}, \"entropy\": {}}}",
+                    bit.ones, bit.zeroes, bit.entropy
+                )
+            })
+            .collect::<Vec<String>>();
+        let value = |val: u128| {
+            format!(
+                "{{\"value\": {}, \"binary\": {}}}",
+                json_string(&val.to_string()),
+                json_string(&self.binary(val))
+            )
+        };
+        let rating = |rating: &std::result::Result<RatingSummary, String>| match rating {
+            Ok(rating) => format!(
+                "{{\"value\": {}, \"binary\": {}, \"passes\": {}}}",
+                json_string(&rating.value.to_string()),
+                json_string(&self.binary(rating.value)),
+                rating.passes
+            ),
+            Err(e) => format!("{{\"error\": {}}}", json_string(e)),
+        };
+
+        format!(
+            "{{\"width\": {}, \"rows\": {}, \"bits\": [{}], \"gamma\": {}, \"epsilon\": {}, \
+             \"oxygen_generator_rating\": {}, \"co2_scrubber_rating\": {}}}",
+            self.width,
+            self.rows,
+            bits.join(", "),
+            value(self.gamma),
+            value(self.epsilon),
+            rating(&self.oxygen_generator_rating),
+            rating(&self.co2_scrubber_rating)
+        )
+    }
+}
+
+impl fmt::Display for DiagnosticSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{} rows of {} bits", self.rows, self.width)?;
+        writeln!(f, "Bit  Ones  Zeroes  Entropy")?;
+        for (idx, bit) in self.bits.iter().enumerate() {
+            writeln!(
+                f,
+                "{:>3}  {:>4}  {:>6}  {:.4}",
+                idx, bit.ones, bit.zeroes, bit.entropy
+            )?;
+        }
+        writeln!(f, "Gamma: {} ({})", self.binary(self.gamma), self.gamma)?;
+        writeln!(
+            f,
+            "Epsilon: {} ({})",
+            self.binary(self.epsilon),
+            self.epsilon
+        )?;
+        for (name, rating) in [
+            ("Oxygen generator rating", &self.oxygen_generator_rating),
+            ("CO2 scrubber rating", &self.co2_scrubber_rating),
+        ] {
+            match rating {
+                Ok(rating) => writeln!(
+                    f,
+                    "{}: {} ({}) after {} passes",
+                    name,
+                    self.binary(rating.value),
+                    rating.value,
+                    rating.passes
+                )?,
+                Err(e) => writeln!(f, "{}: none, {}", name, e)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Shannon entropy, in bits, of a bit that is set in `ones` out of `total` rows
+fn bit_entropy(ones: usize, total: usize) -> f64 {
+    [ones, total - ones]
+        .iter()
+        .filter(|count| **count > 0)
+        .map(|count| {
+            let p = *count as f64 / total as f64;
+            // Rather than `-p * p.log2()`, which is -0 for a bit every row agrees on
+            p * (1.0 / p).log2()
+        })
+        .sum()
+}
+
+/// One pass of a `BitFilter`
+#[derive(Debug, PartialEq)]
+struct FilterStep {
+    /// The bit position the decision was made on, counting from the left
+    idx: usize,
+    ones: usize,
+    zeroes: usize,
+    /// `None` if ones and zeroes were equally common
+    most_common: Option<u128>,
+    /// The bit value rows needed at `idx` to survive the pass
+    kept: u128,
+    survivors: Vec<u128>,
+}
+
+/// Every pass a filter made, see `BitFilter::run_with_trace`
+#[derive(Debug, PartialEq)]
+struct Trace {
+    width: usize,
+    steps: Vec<FilterStep>,
+}
+
+impl fmt::Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for step in self.steps.iter() {
+            let most_common = match step.most_common {
+                Some(bit) => format!("most common {}", bit),
+                None => "tied".into(),
+            };
+            writeln!(
+                f,
+                "Bit {}: {} ones, {} zeroes, {}, keeping {} ({} left)",
+                step.idx,
+                step.ones,
+                step.zeroes,
+                most_common,
+                step.kept,
+                step.survivors.len()
+            )?;
+            for survivor in step.survivors.iter() {
+                writeln!(f, "    {:0width$b}", survivor, width = self.width)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Check that the lines of a report can be used: there has to be at least one line, only '0' and
+/// '1' are allowed, and every line has to be as wide as the first one. Empty lines are only
+/// allowed at the end of the report.
+///
+/// Returns the lines holding bits, or an error pointing at the (1-based) line and column of the
+/// first problem found.
+fn validate_report<'a>(lines: impl Iterator<Item = &'a str>) -> Result<Vec<&'a str>> {
+    let mut valid: Vec<&str> = vec![];
+    let mut empty_line = None;
+
+    for (idx, line) in lines.enumerate() {
+        let number = idx + 1;
+
+        if line.is_empty() {
+            empty_line.get_or_insert(number);
+            continue;
+        }
+        if let Some(empty) = empty_line {
+            return Err(Box::new(Day3Error(format!(
+                "line {}: empty line in the middle of the report",
+                empty
+            ))));
+        }
+
+        let width = valid.first().unwrap_or(&line).len();
+        validate_line(number, line, width)?;
+
+        valid.push(line);
+    }
+
+    if valid.is_empty() {
+        return Err(Box::new(Day3Error("No elements to process".into())));
+    }
+
+    Ok(valid)
+}
+
+/// Check that a single non-empty line only holds '0' and '1' and is `width` bits wide, see
+/// `validate_report`
+fn validate_line(number: usize, line: &str, width: usize) -> std::result::Result<(), Day3Error> {
+    if let Some((column, char)) = line
+        .chars()
+        .enumerate()
+        .find(|(_, char)| *char != '0' && *char != '1')
+    {
+        return Err(Day3Error(format!(
+            "line {}, column {}: expected '0' or '1', got {:?}",
+            number,
+            column + 1,
+            char
+        )));
+    }
+
+    if line.len() != width {
+        return Err(Day3Error(format!(
+            "line {}: expected {} bits like the first line, got {}",
+            number,
+            width,
+            line.len()
+        )));
+    }
+
+    Ok(())
+}
+
+/// Count the rows that have the bit at `shift` set
+fn count_ones_at(rows: &[u128], shift: usize) -> usize {
+    rows.iter().filter(|row| (*row >> shift) & 1 == 1).count()
+}
+
+/// Go through a list of bits and calculate the counts of ones and zeroes. Rather then storing the
+/// number of ones and number of zeroes, just store the number of ones minus the number of zeroes.
+/// If the end result is positive, the most common bit is 1, if negative, then most common is 0
+fn get_bit_counts<'a>(lines: impl Iterator<Item = &'a str>) -> Result<Vec<i32>> {
+    Ok(DiagnosticReport::parse(lines)?.bit_counts())
+}
+
+/// Same as `get_bit_counts`, but with the lines split across `threads` threads that each count
+/// their own lines before the counts are added up. The lines are checked the same way as in
+/// `validate_report`.
+fn get_bit_counts_parallel(lines: &[&str], threads: usize) -> Result<Vec<i32>> {
+    // Only empty lines at the end of the report are allowed
+    let end = lines.len()
+        - lines
+            .iter()
+            .rev()
+            .take_while(|line| line.is_empty())
+            .count();
+    let lines = &lines[..end];
+
+    let width = match lines.first() {
+        Some(line) => line.len(),
+        None => return Err(Box::new(Day3Error("No elements to process".into()))),
+    };
+    let chunk_size = chunk_size(lines.len(), threads);
+
+    thread::scope(|scope| {
+        let handles = lines
+            .chunks(chunk_size)
+            .enumerate()
+            .map(|(chunk_idx, chunk)| {
+                scope.spawn(move || count_lines(chunk, chunk_idx * chunk_size, width))
+            })
+            .collect::<Vec<_>>();
+
+        let mut counts = vec![0; width];
+        for handle in handles {
+            let chunk_counts = handle
+                .join()
+                .map_err(|_| Day3Error("Counting thread panicked".into()))??;
+            for (total, count) in counts.iter_mut().zip(chunk_counts) {
+                *total += count;
+            }
+        }
+        Ok(counts)
+    })
+}
+
+/// Ones minus zeroes for each bit of a chunk of lines, where `offset` is the number of lines
+/// before the chunk, for error messages
+fn count_lines(
+    lines: &[&str],
+    offset: usize,
+    width: usize,
+) -> std::result::Result<Vec<i32>, Day3Error> {
+    let mut counts = vec![0; width];
+
+    for (idx, line) in lines.iter().enumerate() {
+        let number = offset + idx + 1;
+        if line.is_empty() {
+            return Err(Day3Error(format!(
+                "line {}: empty line in the middle of the report",
+                number
+            )));
+        }
+        validate_line(number, line, width)?;
+
+        for (count, bit) in counts.iter_mut().zip(line.bytes()) {
+            match bit {
+                b'1' => *count += 1,
+                _ => *count -= 1,
+            }
+        }
+    }
+
+    Ok(counts)
+}
+
+/// The ones at each of the lowest `width` bits of the rows, indexed by shift
+fn count_ones_per_bit(rows: &[u128], width: usize) -> [u64; MAX_WIDTH] {
+    let mut ones = [0; MAX_WIDTH];
+
+    for row in rows {
+        for (shift, count) in ones[..width].iter_mut().enumerate() {
+            *count += ((row >> shift) & 1) as u64;
+        }
+    }
+
+    ones
+}
+
+/// How many items each thread gets when splitting `total` items across `threads` threads
+fn chunk_size(total: usize, threads: usize) -> usize {
+    let threads = threads.max(1);
+    total.div_ceil(threads).max(1)
+}
+
+fn calculate_gamma_epsilon(bit_counts: Vec<i32>, tie_break: TieBreak) -> Result<(u128, u128)> {
+    if bit_counts.is_empty() || bit_counts.len() > MAX_WIDTH {
+        return Err(Box::new(Day3Error(format!(
+            "Can't calculate gamma and epsilon for {} bits",
+            bit_counts.len()
+        ))));
+    }
+
+    // Get the most common bits from the bit counts to calculate the gamma
+    let mut gamma: u128 = 0;
+    for (idx, balance) in bit_counts.iter().enumerate() {
+        gamma = (gamma << 1) | tie_break.most_common(*balance, idx)?;
+    }
+
+    // The epsilon is just the gamma bit flipped
+    let mask = u128::MAX >> (MAX_WIDTH - bit_counts.len());
+    let epsilon = !gamma & mask;
+
+    Ok((gamma, epsilon))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const EXAMPLE: &str = include_str!("example.txt");
+
+    /// Parse the lines and "boil" them down to one row, following the rules from Day 3 Part 2
+    fn boil_to_one<'a>(
+        lines: impl Iterator<Item = &'a str>,
+        keep_most_common: bool,
+        tie_break: TieBreak,
+    ) -> Result<u128> {
+        DiagnosticReport::parse(lines)?.rating(keep_most_common, tie_break)
+    }
+
+    #[test]
+    fn test_get_bit_counts() {
+        let lines = vec!["1010", "0001", "0011", ""].into_iter();
+
+        assert_eq!(get_bit_counts(lines).unwrap(), vec![-1, -3, 1, 1]);
+    }
+
+    #[test]
+    fn test_get_bit_counts_parallel() {
+        let contents = generate_report(1000, 12);
+        let lines = contents.split('\n').collect::<Vec<&str>>();
+        let expected = get_bit_counts(lines.iter().copied()).unwrap();
+
+        for threads in [1, 2, 3, 7, 2000] {
+            assert_eq!(get_bit_counts_parallel(&lines, threads).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_get_bit_counts_parallel_invalid() {
+        let lines = vec!["1010", "0001", "0011", "0101", "01x1", ""];
+
+        let err = get_bit_counts_parallel(&lines, 2).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid input: line 5, column 3: expected '0' or '1', got 'x'"
+        );
+
+        let lines = vec!["1010", "", "0011"];
+        assert!(get_bit_counts_parallel(&lines, 2).is_err());
+
+        let lines = vec!["", ""];
+        assert!(get_bit_counts_parallel(&lines, 2).is_err());
+    }
+
+    #[test]
+    fn test_bit_counts_parallel() {
+        let contents = generate_report(1000, 128);
+        let report = DiagnosticReport::parse(contents.split('\n')).unwrap();
+
+        for threads in [1, 2, 3, 7, 2000] {
+            assert_eq!(
+                report.bit_counts_parallel(threads).unwrap(),
+                report.bit_counts()
+            );
+        }
+    }
+
+    #[test]
+    fn test_calculate_gamma() {
+        let (gamma, _) =
+            calculate_gamma_epsilon(vec![1, -1, 1, 1, -1], TieBreak::GAMMA_EPSILON).unwrap();
+
+        assert_eq!(gamma, 22);
+    }
+
+    #[test]
+    fn test_calculate_epsilon() {
+        let (_, epsilon) =
+            calculate_gamma_epsilon(vec![1, -1, 1, 1, -1], TieBreak::GAMMA_EPSILON).unwrap();
+
+        assert_eq!(epsilon, 9);
+    }
+
+    #[test]
+    fn test_calculate_gamma_epsilon_tie_break() {
+        let bit_counts = vec![1, 0, -1];
+
+        assert_eq!(
+            calculate_gamma_epsilon(bit_counts.clone(), TieBreak::PreferOne).unwrap(),
+            (0b110, 0b001)
+        );
+        assert_eq!(
+            calculate_gamma_epsilon(bit_counts.clone(), TieBreak::PreferZero).unwrap(),
+            (0b100, 0b011)
+        );
+        assert!(calculate_gamma_epsilon(bit_counts, TieBreak::Error).is_err());
+    }
+
+    #[test]
+    fn test_default_tie_breaks() {
+        // Ties leave a 0 in the gamma, but make the oxygen generator rating keep ones
+        let input = "110\n001\n";
+        let bit_counts = get_bit_counts(input.split('\n')).unwrap();
+
+        assert_eq!(
+            calculate_gamma_epsilon(bit_counts, TieBreak::GAMMA_EPSILON).unwrap(),
+            (0b000, 0b111)
+        );
+        assert_eq!(part1(input).unwrap(), Answer::Unsigned(0));
+        assert_eq!(part2(input).unwrap(), Answer::Unsigned(0b110));
+    }
+
+    #[test]
+    fn test_boil_to_one_tie_break() {
+        let lines = vec!["10", "01"];
+
+        assert_eq!(
+            boil_to_one(lines.clone().into_iter(), true, TieBreak::PreferOne).unwrap(),
+            0b10
+        );
+        assert_eq!(
+            boil_to_one(lines.clone().into_iter(), false, TieBreak::PreferOne).unwrap(),
+            0b01
+        );
+        assert_eq!(
+            boil_to_one(lines.clone().into_iter(), true, TieBreak::PreferZero).unwrap(),
+            0b01
+        );
+        assert_eq!(
+            boil_to_one(lines.clone().into_iter(), false, TieBreak::PreferZero).unwrap(),
+            0b10
+        );
+        assert!(boil_to_one(lines.into_iter(), true, TieBreak::Error).is_err());
+    }
+
+    #[test]
+    fn test_boil_to_one_keep_most_common() {
+        let lines = vec![
+            "00100", "11110", "10110", "10111", "10101", "01111", "00111", "11100", "10000",
+            "11001", "00010", "01010",
+        ]
+        .into_iter();
+
+        assert_eq!(boil_to_one(lines, true, TieBreak::RATINGS).unwrap(), 23);
+    }
+
+    #[test]
+    fn test_boil_to_one_keep_least_common() {
+        let lines = vec![
+            "00100", "11110", "10110", "10111", "10101", "01111", "00111", "11100", "10000",
+            "11001", "00010", "01010",
+        ]
+        .into_iter();
+
+        assert_eq!(boil_to_one(lines, false, TieBreak::RATINGS).unwrap(), 10);
+    }
+
+    #[test]
+    fn test_diagnostic_report_parse() {
+        let lines = vec!["1010", "0001", "0011", ""].into_iter();
+
+        assert_eq!(
+            DiagnosticReport::parse(lines).unwrap(),
+            DiagnosticReport {
+                width: 4,
+                rows: vec![0b1010, 0b0001, 0b0011],
+            }
+        );
+    }
+
+    #[test]
+    fn test_validate_report() {
+        let lines = vec!["1010", "0001", "0011", ""].into_iter();
+
+        assert_eq!(
+            validate_report(lines).unwrap(),
+            vec!["1010", "0001", "0011"]
+        );
+    }
+
+    #[test]
+    fn test_validate_report_invalid() {
+        let cases = vec![
+            (vec![], "No elements to process"),
+            (vec!["", ""], "No elements to process"),
+            (
+                vec!["1010", "10110"],
+                "line 2: expected 4 bits like the first line, got 5",
+            ),
+            (
+                vec!["1010", "101"],
+                "line 2: expected 4 bits like the first line, got 3",
+            ),
+            (
+                vec!["1010", "0001", "10x0"],
+                "line 3, column 3: expected '0' or '1', got 'x'",
+            ),
+            (
+                vec!["1010\r", "0001\r"],
+                "line 1, column 5: expected '0' or '1', got '\\r'",
+            ),
+            (
+                vec!["1010", "", "0001"],
+                "line 2: empty line in the middle of the report",
+            ),
+        ];
+
+        for (lines, message) in cases {
+            let err = validate_report(lines.clone().into_iter()).unwrap_err();
+
+            assert_eq!(
+                err.to_string(),
+                format!("Invalid input: {}", message),
+                "{:?}",
+                lines
+            );
+        }
+    }
+
+    #[test]
+    fn test_parsing_functions_validate() {
+        let lines = vec!["00100", "11110", "1011"];
+
+        assert!(get_bit_counts(lines.clone().into_iter()).is_err());
+        assert!(boil_to_one(lines.clone().into_iter(), true, TieBreak::RATINGS).is_err());
+        assert!(boil_to_one(lines.into_iter(), false, TieBreak::RATINGS).is_err());
+    }
+
+    #[test]
+    fn test_diagnostic_report_parse_invalid() {
+        assert!(DiagnosticReport::parse(vec![""].into_iter()).is_err());
+        assert!(DiagnosticReport::parse(vec!["1010", "101"].into_iter()).is_err());
+        assert!(DiagnosticReport::parse(vec!["1010", "1021"].into_iter()).is_err());
+        assert!(DiagnosticReport::parse(vec!["1".repeat(129).as_str()].into_iter()).is_err());
+    }
+
+    #[test]
+    fn test_wide_reports() {
+        for width in [32, 64, 128] {
+            let ones = "1".repeat(width);
+            let mut last_bit_only = "0".repeat(width);
+            last_bit_only.replace_range(width - 1.., "1");
+            let lines = vec![ones.as_str(), ones.as_str(), last_bit_only.as_str()];
+
+            let bit_counts = get_bit_counts(lines.clone().into_iter()).unwrap();
+            let (gamma, epsilon) =
+                calculate_gamma_epsilon(bit_counts, TieBreak::GAMMA_EPSILON).unwrap();
+            let all_ones = u128::MAX >> (128 - width);
+
+            assert_eq!(gamma, all_ones);
+            assert_eq!(epsilon, 0);
+            assert_eq!(
+                boil_to_one(lines.clone().into_iter(), false, TieBreak::RATINGS).unwrap(),
+                1
+            );
+            assert!(boil_to_one(lines.into_iter(), true, TieBreak::RATINGS).is_err());
+        }
+    }
+
+    #[test]
+    fn test_multiply_wide() {
+        assert_eq!(multiply(3, 4), Answer::Unsigned(12));
+        assert_eq!(
+            multiply(u128::MAX, 2),
+            Answer::Text("680564733841876926926749214863536422910".into())
+        );
+        assert_eq!(
+            multiply(u128::MAX, u128::MAX).to_string(),
+            "115792089237316195423570985008687907852589419931798687112530834793049593217025"
+        );
+        assert_eq!(
+            multiply(1 << 127, 1 << 64).to_string(),
+            "3138550867693340381917894711603833208051177722232017256448"
+        );
+    }
+
+    #[test]
+    fn test_wide_product() {
+        // Unlike `test_wide_reports`, both gamma and epsilon take up most of the 128 bits
+        let gamma = "10".repeat(64);
+        let epsilon = "01".repeat(64);
+        let input = [gamma.as_str(), gamma.as_str(), epsilon.as_str()].join("\n");
+
+        assert_eq!(
+            part1(&input).unwrap(),
+            Answer::Text(
+                "25731575386070265649682441113041757300575426651510819358340185509566576270450"
+                    .into()
+            )
+        );
+    }
+
+    #[test]
+    fn test_diagnostic_report_ratings() {
+        let lines = vec![
+            "00100", "11110", "10110", "10111", "10101", "01111", "00111", "11100", "10000",
+            "11001", "00010", "01010",
+        ]
+        .into_iter();
+        let report = DiagnosticReport::parse(lines).unwrap();
+
+        assert_eq!(report.bit_counts(), vec![2, -2, 4, 2, -2]);
+        assert_eq!(report.rating(true, TieBreak::RATINGS).unwrap(), 23);
+        assert_eq!(report.rating(false, TieBreak::RATINGS).unwrap(), 10);
+    }
+
+    #[test]
+    fn test_rating_with_trace() {
+        let report =
+            DiagnosticReport::parse(vec!["10110", "10111", "10101", "00111"].into_iter()).unwrap();
+
+        let (rating, trace) = report.rating_with_trace(true, TieBreak::RATINGS);
+
+        assert_eq!(rating.unwrap(), 0b10111);
+        assert_eq!(
+            trace.steps,
+            vec![
+                FilterStep {
+                    idx: 0,
+                    ones: 3,
+                    zeroes: 1,
+                    most_common: Some(1),
+                    kept: 1,
+                    survivors: vec![0b10110, 0b10111, 0b10101],
+                },
+                FilterStep {
+                    idx: 1,
+                    ones: 0,
+                    zeroes: 3,
+                    most_common: Some(0),
+                    kept: 0,
+                    survivors: vec![0b10110, 0b10111, 0b10101],
+                },
+                FilterStep {
+                    idx: 2,
+                    ones: 3,
+                    zeroes: 0,
+                    most_common: Some(1),
+                    kept: 1,
+                    survivors: vec![0b10110, 0b10111, 0b10101],
+                },
+                FilterStep {
+                    idx: 3,
+                    ones: 2,
+                    zeroes: 1,
+                    most_common: Some(1),
+                    kept: 1,
+                    survivors: vec![0b10110, 0b10111],
+                },
+                FilterStep {
+                    idx: 4,
+                    ones: 1,
+                    zeroes: 1,
+                    most_common: None,
+                    kept: 1,
+                    survivors: vec![0b10111],
+                },
+            ]
+        );
+        assert_eq!(
+            trace.to_string().lines().next().unwrap(),
+            "Bit 0: 3 ones, 1 zeroes, most common 1, keeping 1 (3 left)"
+        );
+    }
+
+    #[test]
+    fn test_rating_with_trace_on_error() {
+        let report = DiagnosticReport::parse(vec!["10", "01"].into_iter()).unwrap();
+
+        let (rating, trace) = report.rating_with_trace(true, TieBreak::Error);
+
+        assert!(rating.is_err());
+        assert!(trace.steps.is_empty());
+    }
+
+    #[test]
+    fn test_bit_filter_ratings() {
+        let report = DiagnosticReport::parse(
+            vec![
+                "00100", "11110", "10110", "10111", "10101", "01111", "00111", "11100", "10000",
+                "11001", "00010", "01010",
+            ]
+            .into_iter(),
+        )
+        .unwrap();
+
+        let oxygen = BitFilter::new(Majority(TieBreak::RATINGS), PositionOrder::LeftToRight);
+        let co2 = BitFilter::new(Minority(TieBreak::RATINGS), PositionOrder::LeftToRight);
+
+        assert_eq!(oxygen.run(&report).unwrap(), 23);
+        assert_eq!(co2.run(&report).unwrap(), 10);
+    }
+
+    #[test]
+    fn test_bit_filter_orders() {
+        let report = DiagnosticReport::parse(vec!["100", "011", "110"].into_iter()).unwrap();
+
+        let left_to_right = BitFilter::new(Fixed(1), PositionOrder::LeftToRight);
+        let right_to_left = BitFilter::new(Fixed(1), PositionOrder::RightToLeft);
+        let custom = BitFilter::new(Fixed(0), PositionOrder::Custom(vec![2, 1]));
+
+        assert_eq!(left_to_right.run(&report).unwrap(), 0b110);
+        assert_eq!(right_to_left.run(&report).unwrap(), 0b011);
+        assert_eq!(custom.run(&report).unwrap(), 0b100);
+    }
+
+    #[test]
+    fn test_bit_filter_custom_criterion() {
+        let report = DiagnosticReport::parse(vec!["100", "011", "110"].into_iter()).unwrap();
+
+        // Majority on even positions, minority on odd ones
+        let alternating = BitFilter::new(
+            |idx: usize, ones: usize, zeroes: usize| ((ones >= zeroes) as u128) ^ (idx % 2) as u128,
+            PositionOrder::LeftToRight,
+        );
+
+        assert_eq!(alternating.run(&report).unwrap(), 0b100);
+    }
+
+    #[test]
+    fn test_bit_filter_errors() {
+        let report = DiagnosticReport::parse(vec!["100", "011", "110"].into_iter()).unwrap();
+
+        // Nothing has a 1 in every position
+        let fixed = BitFilter::new(Fixed(1), PositionOrder::Custom(vec![0, 2]));
+        assert!(fixed.run(&report).is_err());
+
+        // Two rows share the first bit, and no other positions are checked
+        let partial = BitFilter::new(Fixed(1), PositionOrder::Custom(vec![0]));
+        assert!(partial.run(&report).is_err());
+
+        let outside = BitFilter::new(Fixed(1), PositionOrder::Custom(vec![3]));
+        assert!(outside.run(&report).is_err());
+
+        let not_a_bit = BitFilter::new(|_, _, _| 2, PositionOrder::LeftToRight);
+        assert!(not_a_bit.run(&report).is_err());
+    }
+
+    #[test]
+    fn test_boil_to_one_duplicates() {
+        let lines = vec!["00100", "11110", "00100", "10110", "00100"];
+
+        let err = boil_to_one(lines.into_iter(), true, TieBreak::RATINGS).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Invalid input: 3 identical rows can't be told apart: 00100 (x3)"
+        );
+    }
+
+    #[test]
+    fn test_boil_to_one_only_duplicates() {
+        let lines = vec!["101", "101"];
+
+        assert!(boil_to_one(lines.clone().into_iter(), true, TieBreak::RATINGS).is_err());
+        assert!(boil_to_one(lines.into_iter(), false, TieBreak::RATINGS).is_err());
+    }
+
+    #[test]
+    fn test_boil_to_one_empty() {
+        // Every row has a 1 first, so there are no rows with the least common bit to keep
+        let lines = vec!["10", "11", "11"];
+
+        let err = boil_to_one(lines.into_iter(), false, TieBreak::RATINGS).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Invalid input: No rows left after keeping 0 at bit 0"
+        );
+    }
+
+    #[test]
+    fn test_bit_filter_leftover_rows() {
+        let report = DiagnosticReport::parse(vec!["100", "011", "110"].into_iter()).unwrap();
+
+        let partial = BitFilter::new(Fixed(1), PositionOrder::Custom(vec![0]));
+
+        assert_eq!(
+            partial.run(&report).unwrap_err().to_string(),
+            "Invalid input: 2 rows left after the last position: 100 (x1), 110 (x1)"
+        );
+    }
+
+    #[test]
+    fn test_diagnostic_summary() {
+        let report = DiagnosticReport::parse(
+            vec![
+                "00100", "11110", "10110", "10111", "10101", "01111", "00111", "11100", "10000",
+                "11001", "00010", "01010",
+            ]
+            .into_iter(),
+        )
+        .unwrap();
+
+        let summary = report.summary().unwrap();
+
+        assert_eq!(summary.width, 5);
+        assert_eq!(summary.rows, 12);
+        assert_eq!(
+            summary
+                .bits
+                .iter()
+                .map(|bit| (bit.ones, bit.zeroes))
+                .collect::<Vec<(usize, usize)>>(),
+            vec![(7, 5), (5, 7), (8, 4), (7, 5), (5, 7)]
+        );
+        assert!((summary.bits[0].entropy - 0.9799).abs() < 1e-4);
+        assert_eq!(summary.gamma, 22);
+        assert_eq!(summary.epsilon, 9);
+        assert_eq!(summary.binary(summary.gamma), "10110");
+        assert_eq!(summary.binary(summary.epsilon), "01001");
+        assert_eq!(
+            summary.oxygen_generator_rating,
+            Ok(RatingSummary {
+                value: 23,
+                passes: 5,
+            })
+        );
+        assert_eq!(
+            summary.co2_scrubber_rating,
+            Ok(RatingSummary {
+                value: 10,
+                passes: 3,
+            })
+        );
+    }
+
+    #[test]
+    fn test_diagnostic_summary_failed_rating() {
+        let summary = diagnostic_summary("101\n101\n010").unwrap();
+
+        assert_eq!(summary.gamma, 0b101);
+        assert!(summary.oxygen_generator_rating.is_err());
+        assert_eq!(
+            summary.co2_scrubber_rating,
+            Ok(RatingSummary {
+                value: 0b010,
+                passes: 1,
+            })
+        );
+        assert!(summary
+            .to_string()
+            .contains("Oxygen generator rating: none, Invalid input: "));
+    }
+
+    #[test]
+    fn test_diagnostic_summary_json() {
+        let summary = diagnostic_summary("10\n11\n11\n01").unwrap();
+
+        assert_eq!(
+            summary.to_json(),
+            "{\"width\": 2, \"rows\": 4, \"bits\": [\
+             {\"ones\": 3, \"zeroes\": 1, \"entropy\": 0.8112781244591328}, \
+             {\"ones\": 3, \"zeroes\": 1, \"entropy\": 0.8112781244591328}], \
+             \"gamma\": {\"value\": \"3\", \"binary\": \"11\"}, \
+             \"epsilon\": {\"value\": \"0\", \"binary\": \"00\"}, \
+             \"oxygen_generator_rating\": \
+             {\"error\": \"Invalid input: 2 identical rows can't be told apart: 11 (x2)\"}, \
+             \"co2_scrubber_rating\": {\"value\": \"1\", \"binary\": \"01\", \"passes\": 1}}"
+        );
+    }
+
+    #[test]
+    fn test_bit_entropy() {
+        assert_eq!(bit_entropy(0, 4), 0.0);
+        assert_eq!(bit_entropy(4, 4), 0.0);
+        assert!(bit_entropy(4, 4).is_sign_positive());
+        assert_eq!(bit_entropy(2, 4), 1.0);
+    }
+
+    #[test]
+    fn test_diagnostic_report_rating_duplicates() {
+        let report = DiagnosticReport::parse(vec!["101", "101"].into_iter()).unwrap();
+
+        assert!(report.rating(true, TieBreak::RATINGS).is_err());
+    }
+
+    #[test]
+    fn test_part1_example() {
+        assert_eq!(part1(EXAMPLE).unwrap(), Answer::Unsigned(198));
+    }
+
+    #[test]
+    fn test_part2_example() {
+        assert_eq!(part2(EXAMPLE).unwrap(), Answer::Unsigned(230));
+    }
+
+    #[test]
+    #[cfg_attr(not(has_answer = "2021-3-1"), ignore = "no input.txt with an answer")]
+    fn test_part1() {
+        let (input, expected) = crate::answers::real_input(2021, 3, 1);
+        assert_eq!(part1(&input).unwrap().to_string(), expected);
+    }
+
+    #[test]
+    #[cfg_attr(not(has_answer = "2021-3-2"), ignore = "no input.txt with an answer")]
+    fn test_part2() {
+        let (input, expected) = crate::answers::real_input(2021, 3, 2);
+        assert_eq!(part2(&input).unwrap().to_string(), expected);
+    }
+
+    /// A report of 1 to 40 rows, all `width` bits wide
+    fn report() -> impl Strategy<Value = (usize, Vec<String>)> {
+        (1..=MAX_WIDTH).prop_flat_map(|width| {
+            let row = prop::collection::vec(prop::bool::ANY, width)
+                .prop_map(|bits| bits.iter().map(|&bit| if bit { '1' } else { '0' }).collect());
+            (Just(width), prop::collection::vec(row, 1..40))
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn prop_gamma_and_epsilon_fill_the_width(
+            (width, rows) in report(),
+            prefer_one in prop::bool::ANY,
+        ) {
+            let tie_break = if prefer_one { TieBreak::PreferOne } else { TieBreak::PreferZero };
+            let bit_counts = get_bit_counts(rows.iter().map(String::as_str)).unwrap();
+            let (gamma, epsilon) = calculate_gamma_epsilon(bit_counts, tie_break).unwrap();
+
+            prop_assert_eq!(gamma & epsilon, 0);
+            prop_assert_eq!(gamma + epsilon, u128::MAX >> (MAX_WIDTH - width));
+        }
+
+        #[test]
+        fn prop_parallel_counts_match(
+            (_width, rows) in report(),
+            threads in 1..8usize,
+        ) {
+            let rows = rows.iter().map(String::as_str).collect::<Vec<&str>>();
+
+            prop_assert_eq!(
+                get_bit_counts_parallel(&rows, threads).unwrap(),
+                get_bit_counts(rows.iter().copied()).unwrap()
+            );
+        }
+
+        #[test]
+        fn prop_ratings_are_rows_of_the_report(
+            (_width, rows) in report(),
+            keep_most_common in prop::bool::ANY,
+        ) {
+            let lines = rows.iter().map(String::as_str);
+
+            // Duplicate rows can leave more than one row, which is an error instead
+            if let Ok(rating) = boil_to_one(lines, keep_most_common, TieBreak::RATINGS) {
+                let report = DiagnosticReport::parse(rows.iter().map(String::as_str)).unwrap();
+                prop_assert!(report.rows.contains(&rating));
+            }
+        }
+    }
+}
@@ -0,0 +1,520 @@
+use std::error;
+use std::fmt;
+
+use crate::{Answer, Command, Solver};
+
+type Result<T> = std::result::Result<T, Box<dyn error::Error>>;
+
+#[derive(Debug, PartialEq)]
+struct Day4Error(String);
+
+impl fmt::Display for Day4Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid input: {}", self.0)
+    }
+}
+impl error::Error for Day4Error {}
+
+pub struct Day;
+
+impl Solver for Day {
+    fn parse(&self, input: &str) -> Result<()> {
+        parse_game(input)?;
+        Ok(())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer> {
+        Ok(part1(input)?.into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer> {
+        Ok(part2(input)?.into())
+    }
+
+    fn commands(&self) -> &'static [Command] {
+        &[Command {
+            name: "card",
+            usage: "<number>",
+            run: show_card,
+        }]
+    }
+}
+
+/// Play the game for one card, counted from 1, and print it as it is when it wins
+fn show_card(args: &[String], input: &str) -> Result<()> {
+    let (draws, cards) = parse_game(input)?;
+    let number = match args.first().map(|number| number.parse::<usize>()) {
+        Some(Ok(number)) if (1..=cards.len()).contains(&number) => number,
+        _ => {
+            return Err(Box::new(Day4Error(format!(
+                "Expected a card number from 1 to {}",
+                cards.len()
+            ))))
+        }
+    };
+
+    let mut card = cards[number - 1];
+    for (turn, &draw) in draws.iter().enumerate() {
+        card.check(draw);
+        if card.won() {
+            print!("{}", card);
+            println!(
+                "Card {} wins on draw {} ({}) with a score of {}",
+                number,
+                turn + 1,
+                draw,
+                card.score(draw)?
+            );
+            return Ok(());
+        }
+    }
+
+    print!("{}", card);
+    println!("Card {} never wins", number);
+    Ok(())
+}
+
+/// Parse the draws from the first line and the cards that follow, 5 lines each
+fn parse_game(input: &str) -> Result<(Vec<i32>, Vec<Card>)> {
+    let mut lines = input.split('\n');
+
+    // First line is moves
+    let draws = lines
+        .next()
+        .unwrap_or_default()
+        .split(',')
+        .map(|draw| draw.parse::<i32>())
+        .collect::<std::result::Result<Vec<i32>, _>>()?;
+
+    // The cards are separated by empty lines
+    let rows = lines
+        .filter(|line| !line.trim().is_empty())
+        .collect::<Vec<&str>>();
+    let cards = rows
+        .chunks(5)
+        .map(|card| Card::try_from(card.join("\n").as_str()))
+        .collect::<std::result::Result<Vec<Card>, Day4Error>>()?;
+    if cards.is_empty() {
+        return Err(Box::new(Day4Error("No cards".into())));
+    }
+
+    Ok((draws, cards))
+}
+
+fn part1(input: &str) -> Result<i64> {
+    let (draws, mut cards) = parse_game(input)?;
+    let mut draws = draws.into_iter();
+
+    loop {
+        // Play the draws until first win
+        let draw = match draws.next() {
+            Some(draw) => draw,
+            None => return Err(Box::new(Day4Error("Ran out of draws".into()))),
+        };
+        for card in cards.iter_mut() {
+            card.check(draw);
+        }
+        if let Some(card) = cards.iter().find(|card| card.won()) {
+            return card.score(draw);
+        }
+    }
+}
+
+fn part2(input: &str) -> Result<i64> {
+    let (draws, mut cards) = parse_game(input)?;
+    let mut draws = draws.into_iter();
+
+    let card_count = cards.len();
+    let mut last_board_idx: Option<usize> = None;
+
+    loop {
+        // Play the draws until last win
+        let draw = match draws.next() {
+            Some(draw) => draw,
+            None => return Err(Box::new(Day4Error("Ran out of draws".into()))),
+        };
+        for card in cards.iter_mut() {
+            card.check(draw);
+        }
+        let total_wins = cards.iter().filter(|card| card.won()).count();
+        if total_wins + 1 == card_count && last_board_idx.is_none() {
+            // We have all except one winning cards, figure out which one is the last one
+            last_board_idx = cards.iter().position(|card| !card.won());
+        }
+        if let Some(idx) = last_board_idx {
+            if cards[idx].won() {
+                return cards[idx].score(draw);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Cell {
+    val: i32,
+    checked: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Card {
+    cells: [[Cell; 5]; 5],
+}
+
+impl Card {
+    /// Check if the card is a winning card
+    fn won(self) -> bool {
+        // Check any rows
+        if self
+            .cells
+            .iter()
+            .any(|row| row.iter().all(|cell| cell.checked))
+        {
+            return true;
+        }
+        // Check any columns, diagonals don't count
+        (0..5).any(|row_idx| (0..5).all(|col_idx| self.cells[col_idx][row_idx].checked))
+    }
+
+    fn unchecked_sum(self) -> i64 {
+        self.cells
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .filter(|cell| !cell.checked)
+                    .map(|cell| i64::from(cell.val))
+                    .sum::<i64>()
+            })
+            .sum()
+    }
+
+    /// The score of a winning card, the sum of its unchecked numbers times the last draw
+    fn score(self, draw: i32) -> Result<i64> {
+        self.unchecked_sum()
+            .checked_mul(draw.into())
+            .ok_or_else(|| Box::new(Day4Error("Score overflows".into())).into())
+    }
+
+    fn check(&mut self, val: i32) {
+        for row in self.cells.iter_mut() {
+            for cell in row.iter_mut() {
+                if cell.val == val {
+                    cell.checked = true;
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// The numbers of the card, with the checked ones marked
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.cells.iter() {
+            let cells = row
+                .iter()
+                .map(|cell| format!("{:>2}{}", cell.val, if cell.checked { '*' } else { ' ' }))
+                .collect::<Vec<String>>();
+            writeln!(f, "{}", cells.join(" ").trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<&str> for Card {
+    type Error = Day4Error;
+
+    fn try_from(raw_input: &str) -> std::result::Result<Self, Self::Error> {
+        let rows = raw_input
+            .split('\n')
+            .map(|row| row.split_whitespace().collect::<Vec<&str>>())
+            .collect::<Vec<Vec<&str>>>();
+        if rows.len() != 5 || rows.iter().any(|row| row.len() != 5) {
+            return Err(Day4Error(format!(
+                "Cards must be 5 rows of 5 numbers: {:?}",
+                raw_input
+            )));
+        }
+
+        let mut cells = [[Cell {
+            val: 0,
+            checked: false,
+        }; 5]; 5];
+        for (cells, row) in cells.iter_mut().zip(rows) {
+            for (cell, val) in cells.iter_mut().zip(row) {
+                cell.val = val
+                    .parse()
+                    .map_err(|e| Day4Error(format!("Invalid number {} on card: {}", val, e)))?;
+            }
+        }
+
+        Ok(Card { cells })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const EXAMPLE: &str = include_str!("example.txt");
+
+    #[test]
+    fn test_part1_example() {
+        assert_eq!(part1(EXAMPLE).unwrap(), 4512);
+    }
+
+    #[test]
+    fn test_part2_example() {
+        assert_eq!(part2(EXAMPLE).unwrap(), 1924);
+    }
+
+    #[test]
+    #[cfg_attr(not(has_answer = "2021-4-1"), ignore = "no input.txt with an answer")]
+    fn test_part1() {
+        let (input, expected) = crate::answers::real_input(2021, 4, 1);
+        assert_eq!(part1(&input).unwrap().to_string(), expected);
+    }
+
+    #[test]
+    #[cfg_attr(not(has_answer = "2021-4-2"), ignore = "no input.txt with an answer")]
+    fn test_part2() {
+        let (input, expected) = crate::answers::real_input(2021, 4, 2);
+        assert_eq!(part2(&input).unwrap().to_string(), expected);
+    }
+
+    #[test]
+    fn test_card_from_str() {
+        let input = "22 13 17 11  0\n8  2 23  4 24\n21  9 14 16  7\n6 10  3 18  5\n1 12 20 15 19";
+
+        let card = Card::try_from(input).unwrap();
+
+        #[rustfmt::skip]
+        assert_eq!(
+            card,
+            Card {
+                cells: [
+                    [
+                        Cell { val: 22, checked: false },
+                        Cell { val: 13, checked: false },
+                        Cell { val: 17, checked: false },
+                        Cell { val: 11, checked: false },
+                        Cell { val: 0, checked: false },
+                    ],
+                    [
+                        Cell { val: 8, checked: false },
+                        Cell { val: 2, checked: false },
+                        Cell { val: 23, checked: false },
+                        Cell { val: 4, checked: false },
+                        Cell { val: 24, checked: false },
+                    ],
+                    [
+                        Cell { val: 21, checked: false },
+                        Cell { val: 9, checked: false },
+                        Cell { val: 14, checked: false },
+                        Cell { val: 16, checked: false },
+                        Cell { val: 7, checked: false },
+                    ],
+                    [
+                        Cell { val: 6, checked: false },
+                        Cell { val: 10, checked: false },
+                        Cell { val: 3, checked: false },
+                        Cell { val: 18, checked: false },
+                        Cell { val: 5, checked: false },
+                    ],
+                    [
+                        Cell { val: 1, checked: false },
+                        Cell { val: 12, checked: false },
+                        Cell { val: 20, checked: false },
+                        Cell { val: 15, checked: false },
+                        Cell { val: 19, checked: false },
+                    ],
+                ]
+            }
+        );
+    }
+
+    #[test]
+    fn test_card_try_from_invalid() {
+        let invalid = [
+            "",
+            "22 13 17 11  0\n8  2 23  4 24\n21  9 14 16  7\n6 10  3 18  5",
+            "22 13 17 11  0\n8  2 23  4 24\n21  9 14 16  7\n6 10  3 18  5\n1 12 20 15",
+            "22 13 17 11  0\n8  2 23  4 24\n21  9 14 16  7\n6 10  3 18  5\n1 12 20 15 x",
+        ];
+
+        for input in invalid {
+            assert!(Card::try_from(input).is_err(), "{:?} should be invalid", input);
+        }
+    }
+
+    #[test]
+    fn test_parse_game_invalid() {
+        let card = "22 13 17 11  0\n8  2 23  4 24\n21  9 14 16  7\n6 10  3 18  5\n1 12 20 15 19";
+
+        assert!(parse_game("").is_err());
+        assert!(parse_game("7,4,9\n").is_err());
+        assert!(parse_game(&format!("7,x,9\n\n{}\n", card)).is_err());
+        assert!(parse_game(&format!("7,4,9\n\n{}\n\n1 2 3\n", card)).is_err());
+        assert!(parse_game(&format!("7,4,9\n\n{}\n\n\n", card)).is_ok());
+    }
+
+    #[test]
+    fn test_score_overflow() {
+        let game = "2147483647\n\n2147483647 1 1 1 1\n1 1 1 1 1\n1 1 1 1 1\n1 1 1 1 1\n1 1 1 1 1\n";
+
+        assert!(part1(game).is_err());
+        assert!(part2(game).is_err());
+    }
+
+    #[test]
+    fn test_card_won_row() {
+        let input = "22 13 17 11  0\n8  2 23  4 24\n21  9 14 16  7\n6 10  3 18  5\n1 12 20 15 19";
+        let mut card = Card::try_from(input).unwrap();
+
+        assert!(!card.won());
+
+        for idx in 0..5 {
+            card.cells[2][idx].checked = true;
+        }
+
+        assert!(card.won());
+    }
+
+    #[test]
+    fn test_card_won_column() {
+        let input = "22 13 17 11  0\n8  2 23  4 24\n21  9 14 16  7\n6 10  3 18  5\n1 12 20 15 19";
+        let mut card = Card::try_from(input).unwrap();
+
+        assert!(!card.won());
+
+        for idx in 0..5 {
+            card.cells[idx][2].checked = true;
+        }
+
+        assert!(card.won());
+    }
+
+    #[test]
+    fn test_card_diagonal_doesnt_win_1() {
+        let input = "22 13 17 11  0\n8  2 23  4 24\n21  9 14 16  7\n6 10  3 18  5\n1 12 20 15 19";
+        let mut card = Card::try_from(input).unwrap();
+
+        for idx in 0..5 {
+            card.cells[idx][idx].checked = true;
+        }
+
+        assert!(!card.won());
+    }
+
+    #[test]
+    fn test_card_diagonal_doesnt_win_2() {
+        let input = "22 13 17 11  0\n8  2 23  4 24\n21  9 14 16  7\n6 10  3 18  5\n1 12 20 15 19";
+        let mut card = Card::try_from(input).unwrap();
+
+        for idx in 0..5 {
+            card.cells[4 - idx][idx].checked = true;
+        }
+
+        assert!(!card.won());
+    }
+
+    #[test]
+    fn test_card_display() {
+        let input = "22 13 17 11  0\n8  2 23  4 24\n21  9 14 16  7\n6 10  3 18  5\n1 12 20 15 19";
+        let mut card = Card::try_from(input).unwrap();
+        card.check(13);
+        card.check(19);
+
+        assert_eq!(
+            card.to_string(),
+            "22  13* 17  11   0\n 8   2  23   4  24\n21   9  14  16   7\n 6  10   3  18   5\n 1  12  20  15  19*\n"
+        );
+    }
+
+    #[test]
+    fn test_card_unchecked_sum() {
+        let input = "22 13 17 11  0\n8  2 23  4 24\n21  9 14 16  7\n6 10  3 18  5\n1 12 20 15 19";
+        let mut card = Card::try_from(input).unwrap();
+
+        assert_eq!(card.unchecked_sum(), 300);
+
+        card.cells[0][0].checked = true; // 22
+        card.cells[2][0].checked = true; // 21
+        card.cells[4][4].checked = true; // 19
+
+        assert_eq!(card.unchecked_sum(), 300 - 22 - 21 - 19);
+    }
+
+    #[test]
+    fn test_card_check_val() {
+        let input = "22 13 17 11  0\n8  2 23  4 24\n21  9 14 16  7\n6 10  3 18  5\n1 12 20 15 19";
+        let mut card = Card::try_from(input).unwrap();
+
+        card.check(17);
+        assert!(card.cells[0][2].checked);
+    }
+
+    /// The text of a card with 25 different numbers
+    fn card() -> impl Strategy<Value = (Vec<i32>, String)> {
+        prop::sample::subsequence((0..100).collect::<Vec<i32>>(), 25)
+            .prop_shuffle()
+            .prop_map(|numbers| {
+                let text = numbers
+                    .chunks(5)
+                    .map(|row| {
+                        row.iter()
+                            .map(|val| format!("{:>2}", val))
+                            .collect::<Vec<String>>()
+                            .join(" ")
+                    })
+                    .collect::<Vec<String>>()
+                    .join("\n");
+                (numbers, text)
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn prop_card_with_all_its_numbers_won((numbers, text) in card()) {
+            let mut card = Card::try_from(text.as_str()).unwrap();
+            for val in numbers {
+                card.check(val);
+            }
+
+            prop_assert!(card.won());
+            prop_assert_eq!(card.unchecked_sum(), 0);
+        }
+
+        #[test]
+        fn prop_card_needs_five_numbers_to_win(
+            (numbers, text) in card(),
+            count in 0..5usize,
+        ) {
+            let mut card = Card::try_from(text.as_str()).unwrap();
+            for &val in &numbers[..count] {
+                card.check(val);
+            }
+
+            prop_assert!(!card.won());
+            prop_assert_eq!(
+                card.unchecked_sum(),
+                numbers[count..].iter().map(|&val| i64::from(val)).sum::<i64>()
+            );
+        }
+
+        #[test]
+        fn prop_card_won_by_any_row_or_column(
+            (numbers, text) in card(),
+            line in 0..5usize,
+            column in prop::bool::ANY,
+        ) {
+            let mut card = Card::try_from(text.as_str()).unwrap();
+            for idx in 0..5 {
+                let (row, col) = if column { (idx, line) } else { (line, idx) };
+                card.check(numbers[row * 5 + col]);
+            }
+
+            prop_assert!(card.won());
+        }
+    }
+}